@@ -4,6 +4,9 @@ import { DollarToken } from "../target/types/dollar_token";
 import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
 import DollarTokenClient from "./interact";
 
+// Wallets per batch instruction; keeps the transaction under the size limit
+const BATCH_CHUNK_SIZE = 12;
+
 class AccessControlManager {
   private client: DollarTokenClient;
   private program: Program<DollarToken>;
@@ -107,21 +110,37 @@ class AccessControlManager {
     return tx;
   }
 
-  async batchBlacklist(wallets: PublicKey[]): Promise<string[]> {
+  async batchBlacklist(wallets: PublicKey[], reasonCode: number = 0): Promise<string[]> {
     console.log(`🚫 Batch blacklisting ${wallets.length} wallets...`);
     const transactions: string[] = [];
 
-    for (const wallet of wallets) {
+    const [tokenInfoPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("token_info"), this.mint.toBuffer()],
+      this.program.programId
+    );
+
+    // Entry PDAs travel in remaining_accounts, so chunk to fit a transaction
+    for (let i = 0; i < wallets.length; i += BATCH_CHUNK_SIZE) {
+      const chunk = wallets.slice(i, i + BATCH_CHUNK_SIZE);
       try {
-        const tx = await this.addToBlacklist(wallet);
+        const tx = await this.program.methods
+          .batchAddToBlacklist(chunk, reasonCode, "", null, Array(32).fill(0))
+          .accounts({
+            authority: this.client["payer"].publicKey,
+            mint: this.mint,
+            tokenInfo: tokenInfoPda,
+            systemProgram: SystemProgram.programId,
+          })
+          .remainingAccounts(chunk.map((wallet) => this.entryMeta("blacklist", wallet)))
+          .rpc();
         transactions.push(tx);
-        console.log(`  ✅ Blacklisted: ${wallet.toString()}`);
+        console.log(`  ✅ Blacklisted ${chunk.length} wallets: ${tx}`);
       } catch (error) {
-        console.error(`  ❌ Failed to blacklist ${wallet.toString()}:`, error);
+        console.error(`  ❌ Failed to blacklist wallets ${i}-${i + chunk.length - 1}:`, error);
       }
     }
 
-    console.log(`✅ Batch blacklist completed: ${transactions.length}/${wallets.length} successful`);
+    console.log(`✅ Batch blacklist completed: ${transactions.length} transactions`);
     return transactions;
   }
 
@@ -232,25 +251,52 @@ class AccessControlManager {
   }
 
   async batchWhitelist(
-    wallets: Array<{ address: PublicKey; allocation: number }>
+    wallets: Array<{ address: PublicKey; allocation: number }>,
+    decimals: number = 9
   ): Promise<string[]> {
     console.log(`✅ Batch whitelisting ${wallets.length} wallets...`);
     const transactions: string[] = [];
 
-    for (const wallet of wallets) {
+    const [tokenInfoPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("token_info"), this.mint.toBuffer()],
+      this.program.programId
+    );
+
+    for (let i = 0; i < wallets.length; i += BATCH_CHUNK_SIZE) {
+      const chunk = wallets.slice(i, i + BATCH_CHUNK_SIZE);
       try {
-        const tx = await this.addToWhitelist(wallet.address, wallet.allocation);
+        const tx = await this.program.methods
+          .batchAddToWhitelist(
+            chunk.map((wallet) => wallet.address),
+            chunk.map((wallet) => new BN(wallet.allocation * Math.pow(10, decimals)))
+          )
+          .accounts({
+            authority: this.client["payer"].publicKey,
+            mint: this.mint,
+            tokenInfo: tokenInfoPda,
+            systemProgram: SystemProgram.programId,
+          })
+          .remainingAccounts(chunk.map((wallet) => this.entryMeta("whitelist", wallet.address)))
+          .rpc();
         transactions.push(tx);
-        console.log(`  ✅ Whitelisted: ${wallet.address.toString()} - ${wallet.allocation} tokens`);
+        console.log(`  ✅ Whitelisted ${chunk.length} wallets: ${tx}`);
       } catch (error) {
-        console.error(`  ❌ Failed to whitelist ${wallet.address.toString()}:`, error);
+        console.error(`  ❌ Failed to whitelist wallets ${i}-${i + chunk.length - 1}:`, error);
       }
     }
 
-    console.log(`✅ Batch whitelist completed: ${transactions.length}/${wallets.length} successful`);
+    console.log(`✅ Batch whitelist completed: ${transactions.length} transactions`);
     return transactions;
   }
 
+  private entryMeta(prefix: "blacklist" | "whitelist", wallet: PublicKey) {
+    const [pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from(prefix), this.mint.toBuffer(), wallet.toBuffer()],
+      this.program.programId
+    );
+    return { pubkey: pda, isSigner: false, isWritable: true };
+  }
+
   // ============= TRADING CONTROL =============
 
   async toggleTrading(enabled: boolean): Promise<string> {
@@ -1,10 +1,70 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    keccak,
+    program::invoke_signed,
+    program_option::COption,
+};
 use anchor_spl::{
-    token::{self, Mint, Token, TokenAccount, Transfer, MintTo, Burn},
+    metadata::{
+        self, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3, Metadata,
+        UpdateMetadataAccountsV2,
+    },
+    token::Token,
+    token_2022::{initialize_mint2, InitializeMint2, Token2022},
+    token_2022_extensions::{
+        harvest_withheld_tokens_to_mint, transfer_fee_initialize, transfer_fee_set,
+        transfer_hook_initialize, withdraw_withheld_tokens_from_mint, HarvestWithheldTokensToMint,
+        TransferFeeInitialize, TransferFeeSetTransferFee, TransferHookInitialize,
+        WithdrawWithheldTokensFromMint,
+    },
+    token_interface::{self, spl_token_2022::instruction::AuthorityType, Mint, TokenAccount, TokenInterface, TransferChecked, MintTo, Burn, SetAuthority, FreezeAccount, ThawAccount},
+};
+use anchor_spl::token_interface::spl_token_2022::{
+    extension::{
+        transfer_fee::TransferFeeConfig, transfer_hook::TransferHookAccount, BaseStateWithExtensions,
+        ExtensionType, StateWithExtensions,
+    },
+    state::{Account as SplTokenAccount, Mint as SplMint},
 };
+use spl_discriminator::SplDiscriminate;
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
 
 declare_id!("FtAWkh8vpT1DvULYhhtYZhYNuobPmeizR5kbmD4jMy48");
 
+const MINT_AUTHORITY_SEED: &[u8] = b"mint_authority";
+const ADMIN_COUNCIL_SEED: &[u8] = b"admin_council";
+const COUNCIL_AUTHORITY_SEED: &[u8] = b"council_authority";
+const COUNCIL_PROPOSAL_SEED: &[u8] = b"council_proposal";
+const MAX_COUNCIL_MEMBERS: usize = 10;
+const MAX_PROPOSAL_ACCOUNTS: usize = 16;
+const MAX_PROPOSAL_DATA_LEN: usize = 256;
+const QUEUED_ACTION_SEED: &[u8] = b"queued_action";
+const TIMELOCK_AUTHORITY_SEED: &[u8] = b"timelock_authority";
+const MAX_TIMELOCK_DELAY: i64 = 30 * 24 * 60 * 60;
+const ROLE_SEED: &[u8] = b"role";
+const MAX_DECIMALS: u8 = 9;
+const MAX_NAME_LEN: usize = 32;
+const MAX_SYMBOL_LEN: usize = 10;
+const MAX_URI_LEN: usize = 200;
+const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+const MAX_BLACKLIST_REASON_LEN: usize = 100;
+const DEFAULT_APPEAL_WINDOW: i64 = 14 * 24 * 60 * 60;
+const MAX_APPEAL_WINDOW: i64 = 90 * 24 * 60 * 60;
+const BUCKET_SEED: &[u8] = b"bucket";
+const TREASURY_POOL_SEED: &[u8] = b"treasury_pool";
+const MIN_DRAIN_LIQUIDITY_DELAY: i64 = 24 * 60 * 60;
+const LIQUIDITY_LOCK_SEED: &[u8] = b"liquidity_lock";
+const LIQUIDITY_LOCK_VAULT_SEED: &[u8] = b"liquidity_lock_vault";
+const LIMIT_EXEMPTION_SEED: &[u8] = b"limit_exemption";
+const MIN_TRANSFER_LIMITS_DELAY: i64 = 24 * 60 * 60;
+const FEE_EXEMPTION_SEED: &[u8] = b"fee_exemption";
+const MAX_FEE_BASIS_POINTS: u16 = 10_000;
+const VELOCITY_TRACKER_SEED: &[u8] = b"velocity";
+const VELOCITY_WINDOW_HOURS: usize = 24;
+const SECONDS_PER_HOUR: i64 = 60 * 60;
+
 #[program]
 pub mod dollar_token {
     use super::*;
@@ -15,64 +75,146 @@ pub mod dollar_token {
         symbol: String,
         uri: String,
         decimals: u8,
+        max_supply: u64,
     ) -> Result<()> {
+        validate_metadata(&name, &symbol, &uri)?;
+
+        let cpi_accounts = CreateMetadataAccountsV3 {
+            metadata: ctx.accounts.metadata.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            mint_authority: ctx.accounts.mint_authority.to_account_info(),
+            payer: ctx.accounts.authority.to_account_info(),
+            update_authority: ctx.accounts.mint_authority.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        };
+        create_token_metadata(
+            cpi_accounts,
+            ctx.accounts.token_metadata_program.to_account_info(),
+            ctx.bumps.mint_authority,
+            &name,
+            &symbol,
+            &uri,
+        )?;
+
         let token_info = &mut ctx.accounts.token_info;
-        token_info.authority = ctx.accounts.authority.key();
-        token_info.mint = ctx.accounts.mint.key();
+        init_token_info(
+            token_info,
+            ctx.accounts.authority.key(),
+            ctx.accounts.mint.key(),
+            decimals,
+            max_supply,
+            ctx.bumps.mint_authority,
+        )?;
         token_info.name = name;
         token_info.symbol = symbol;
         token_info.uri = uri;
-        token_info.decimals = decimals;
-        token_info.total_supply = 0;
-        token_info.is_initialized = true;
-        token_info.whitelist_enabled = false;
-        token_info.trading_enabled = true;
-        token_info.blacklist_enabled = true;
-        
+
         Ok(())
     }
 
-    pub fn mint_tokens(
-        ctx: Context<MintTokens>,
-        amount: u64,
+    /// Creates a Token-2022 variant of the mint whose transfer hook points
+    /// back at this program, so the trading, blacklist and whitelist checks
+    /// run on every transfer instead of only through `controlled_transfer`.
+    /// Holders transfer it directly with Token-2022; this program's own
+    /// transfer instructions would re-enter the hook and cannot be used.
+    /// The mint also carries the transfer fee extension, which withholds
+    /// the fee in the recipient's account on every transfer.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize_token_2022(
+        ctx: Context<InitializeToken2022>,
+        name: String,
+        symbol: String,
+        uri: String,
+        decimals: u8,
+        max_supply: u64,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
     ) -> Result<()> {
-        // Only authority can mint
-        require!(
-            ctx.accounts.authority.key() == ctx.accounts.token_info.authority,
-            TokenError::UnauthorizedAccess
-        );
+        validate_metadata(&name, &symbol, &uri)?;
+        validate_transfer_fee(transfer_fee_basis_points)?;
+        create_token_2022_mint(
+            ctx.accounts,
+            decimals,
+            transfer_fee_basis_points,
+            maximum_fee,
+        )?;
 
-        let cpi_accounts = MintTo {
+        let cpi_accounts = CreateMetadataAccountsV3 {
+            metadata: ctx.accounts.metadata.to_account_info(),
             mint: ctx.accounts.mint.to_account_info(),
-            to: ctx.accounts.destination.to_account_info(),
-            authority: ctx.accounts.authority.to_account_info(),
+            mint_authority: ctx.accounts.mint_authority.to_account_info(),
+            payer: ctx.accounts.authority.to_account_info(),
+            update_authority: ctx.accounts.mint_authority.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
         };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        create_token_metadata(
+            cpi_accounts,
+            ctx.accounts.token_metadata_program.to_account_info(),
+            ctx.bumps.mint_authority,
+            &name,
+            &symbol,
+            &uri,
+        )?;
 
-        token::mint_to(cpi_ctx, amount)?;
+        let extra_account_metas = transfer_hook_extra_account_metas()?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(
+            &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
+            &extra_account_metas,
+        )?;
 
         let token_info = &mut ctx.accounts.token_info;
-        token_info.total_supply = token_info.total_supply.checked_add(amount).unwrap();
+        init_token_info(
+            token_info,
+            ctx.accounts.authority.key(),
+            ctx.accounts.mint.key(),
+            decimals,
+            max_supply,
+            ctx.bumps.mint_authority,
+        )?;
+        token_info.name = name;
+        token_info.symbol = symbol;
+        token_info.uri = uri;
+        token_info.transfer_fee_basis_points = transfer_fee_basis_points;
+        token_info.maximum_fee = maximum_fee;
 
-        emit!(TokensMinted {
-            mint: ctx.accounts.mint.key(),
-            to: ctx.accounts.destination.key(),
+        Ok(())
+    }
+
+    pub fn mint_tokens(
+        ctx: Context<MintTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+
+        require!(
+            ctx.accounts.token_info.timelock_delays.get(TimelockKind::MintTokens) == 0,
+            TokenError::TimelockRequired
+        );
+
+        record_whitelist_receipt(
+            &ctx.accounts.token_info,
+            &ctx.accounts.destination_whitelist,
             amount,
-            new_supply: token_info.total_supply,
-        });
+        )?;
 
-        Ok(())
+        mint_with_pda(
+            &mut ctx.accounts.token_info,
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.destination.to_account_info(),
+            ctx.accounts.mint_authority.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            amount,
+        )?;
+        record_bucket_mint(&mut ctx.accounts.bucket, amount)
     }
 
     pub fn burn_tokens(
         ctx: Context<BurnTokens>,
         amount: u64,
     ) -> Result<()> {
-        require!(
-            ctx.accounts.authority.key() == ctx.accounts.token_info.authority,
-            TokenError::UnauthorizedAccess
-        );
+        ctx.accounts.role_grant.check_active()?;
 
         let cpi_accounts = Burn {
             mint: ctx.accounts.mint.to_account_info(),
@@ -82,10 +224,13 @@ pub mod dollar_token {
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
 
-        token::burn(cpi_ctx, amount)?;
+        token_interface::burn(cpi_ctx, amount)?;
 
         let token_info = &mut ctx.accounts.token_info;
-        token_info.total_supply = token_info.total_supply.checked_sub(amount).unwrap();
+        token_info.total_supply = token_info
+            .total_supply
+            .checked_sub(amount)
+            .ok_or(TokenError::MathOverflow)?;
 
         emit!(TokensBurned {
             mint: ctx.accounts.mint.key(),
@@ -97,56 +242,411 @@ pub mod dollar_token {
         Ok(())
     }
 
-    pub fn drain_liquidity(
-        ctx: Context<DrainLiquidity>,
-        amount: u64,
+    // ============= TREASURY POOLS =============
+
+    /// Sets the token account every drain pays into. It can only be set
+    /// once so a compromised authority cannot redirect drains.
+    pub fn set_treasury(ctx: Context<SetTreasury>) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        require!(
+            ctx.accounts.token_info.treasury == Pubkey::default(),
+            TokenError::TreasuryAlreadySet
+        );
+
+        let token_info = &mut ctx.accounts.token_info;
+        token_info.treasury = ctx.accounts.treasury.key();
+
+        emit!(TreasurySet {
+            mint: token_info.mint,
+            treasury: token_info.treasury,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Registers a token account as a pool drains may pull from, with at
+    /// most `epoch_cap` leaving it per epoch. Ownership of the pool moves to
+    /// the timelock PDA, so queued drains are the only way out of it.
+    pub fn register_treasury_pool(
+        ctx: Context<RegisterTreasuryPool>,
+        epoch_cap: u64,
     ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        require!(epoch_cap > 0, TokenError::InvalidAmount);
+
+        // A pool that was registered before is still owned by the PDA
+        let timelock_authority = ctx.accounts.timelock_authority.key();
+        if ctx.accounts.liquidity_pool.owner != timelock_authority {
+            let cpi_accounts = SetAuthority {
+                account_or_mint: ctx.accounts.liquidity_pool.to_account_info(),
+                current_authority: ctx.accounts.authority.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+            token_interface::set_authority(
+                cpi_ctx,
+                AuthorityType::AccountOwner,
+                Some(timelock_authority),
+            )?;
+        }
+
+        let now = Clock::get()?;
+        let treasury_pool = &mut ctx.accounts.treasury_pool;
+        treasury_pool.mint = ctx.accounts.mint.key();
+        treasury_pool.pool = ctx.accounts.liquidity_pool.key();
+        treasury_pool.epoch_cap = epoch_cap;
+        treasury_pool.epoch = now.epoch;
+        treasury_pool.epoch_outflow = 0;
+        treasury_pool.bump = ctx.bumps.treasury_pool;
+
+        emit!(TreasuryPoolRegistered {
+            mint: treasury_pool.mint,
+            pool: treasury_pool.pool,
+            epoch_cap,
+            authority: ctx.accounts.authority.key(),
+            timestamp: now.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Removes an empty pool from the drain list and hands its ownership
+    /// back to the treasurer. Anything left in the pool has to leave through
+    /// queued drains first, so deregistering cannot skip the timelock.
+    pub fn deregister_treasury_pool(ctx: Context<DeregisterTreasuryPool>) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
         require!(
-            ctx.accounts.authority.key() == ctx.accounts.token_info.authority,
-            TokenError::UnauthorizedAccess
+            ctx.accounts.liquidity_pool.amount == 0,
+            TokenError::TreasuryPoolNotEmpty
         );
 
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.liquidity_pool.to_account_info(),
-            to: ctx.accounts.treasury.to_account_info(),
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            TIMELOCK_AUTHORITY_SEED,
+            mint_key.as_ref(),
+            &[ctx.bumps.timelock_authority],
+        ];
+        let cpi_accounts = SetAuthority {
+            account_or_mint: ctx.accounts.liquidity_pool.to_account_info(),
+            current_authority: ctx.accounts.timelock_authority.to_account_info(),
+        };
+        token_interface::set_authority(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[&seeds[..]],
+            ),
+            AuthorityType::AccountOwner,
+            Some(ctx.accounts.authority.key()),
+        )?;
+
+        emit!(TreasuryPoolDeregistered {
+            mint: ctx.accounts.treasury_pool.mint,
+            pool: ctx.accounts.treasury_pool.pool,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ============= LIQUIDITY LOCKS =============
+
+    /// Opens an empty lock for `lp_mint`, which may be any SPL or Token-2022
+    /// mint. Nothing deposited can leave before `unlock_ts`.
+    pub fn create_liquidity_lock(
+        ctx: Context<CreateLiquidityLock>,
+        unlock_ts: i64,
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(unlock_ts > now, TokenError::InvalidUnlockTime);
+
+        let lock = &mut ctx.accounts.liquidity_lock;
+        lock.mint = ctx.accounts.mint.key();
+        lock.lp_mint = ctx.accounts.lp_mint.key();
+        lock.vault = ctx.accounts.vault.key();
+        lock.deposited = 0;
+        lock.unlock_ts = unlock_ts;
+        lock.released = false;
+        lock.bump = ctx.bumps.liquidity_lock;
+
+        emit!(LiquidityLockCreated {
+            mint: lock.mint,
+            lp_mint: lock.lp_mint,
+            vault: lock.vault,
+            unlock_ts,
+            authority: ctx.accounts.authority.key(),
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Moves `amount` LP tokens from the authority into the lock's vault.
+    pub fn deposit_liquidity(ctx: Context<DepositLiquidity>, amount: u64) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        require!(amount > 0, TokenError::InvalidAmount);
+        require!(!ctx.accounts.liquidity_lock.released, TokenError::LiquidityAlreadyReleased);
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.source.to_account_info(),
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
             authority: ctx.accounts.authority.to_account_info(),
         };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_program = ctx.accounts.lp_token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.lp_mint.decimals)?;
+
+        let lock = &mut ctx.accounts.liquidity_lock;
+        lock.deposited = lock
+            .deposited
+            .checked_add(amount)
+            .ok_or(TokenError::MathOverflow)?;
+
+        emit!(LiquidityDeposited {
+            mint: lock.mint,
+            lp_mint: lock.lp_mint,
+            amount,
+            deposited: lock.deposited,
+            unlock_ts: lock.unlock_ts,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Pushes the unlock time later. It can never be moved earlier.
+    pub fn extend_liquidity_lock(
+        ctx: Context<ExtendLiquidityLock>,
+        unlock_ts: i64,
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        let lock = &mut ctx.accounts.liquidity_lock;
+        require!(!lock.released, TokenError::LiquidityAlreadyReleased);
+        require!(unlock_ts > lock.unlock_ts, TokenError::UnlockTimeDecrease);
+
+        let old_unlock_ts = lock.unlock_ts;
+        lock.unlock_ts = unlock_ts;
+
+        emit!(LiquidityLockExtended {
+            mint: lock.mint,
+            lp_mint: lock.lp_mint,
+            old_unlock_ts,
+            new_unlock_ts: unlock_ts,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Sends the whole vault to `destination` once `unlock_ts` has passed.
+    pub fn release_liquidity(ctx: Context<ReleaseLiquidity>) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        let now = Clock::get()?.unix_timestamp;
+        let lock = &ctx.accounts.liquidity_lock;
+        require!(!lock.released, TokenError::LiquidityAlreadyReleased);
+        require!(now >= lock.unlock_ts, TokenError::LiquidityStillLocked);
+
+        let amount = ctx.accounts.vault.amount;
+        let seeds = &[
+            LIQUIDITY_LOCK_SEED,
+            lock.mint.as_ref(),
+            lock.lp_mint.as_ref(),
+            &[lock.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.liquidity_lock.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.lp_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.lp_mint.decimals)?;
+
+        let lock = &mut ctx.accounts.liquidity_lock;
+        lock.released = true;
+
+        emit!(LiquidityReleased {
+            mint: lock.mint,
+            lp_mint: lock.lp_mint,
+            amount,
+            destination: ctx.accounts.destination.key(),
+            authority: ctx.accounts.authority.key(),
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Read-only view of a lock. `amount` is the vault's live balance, so
+    /// anyone can check what is actually locked.
+    pub fn get_liquidity_lock(ctx: Context<ViewLiquidityLock>) -> Result<LiquidityLockSummary> {
+        let lock = &ctx.accounts.liquidity_lock;
+        let now = Clock::get()?.unix_timestamp;
+        Ok(LiquidityLockSummary {
+            lp_mint: lock.lp_mint,
+            vault: lock.vault,
+            amount: ctx.accounts.vault.amount,
+            deposited: lock.deposited,
+            unlock_ts: lock.unlock_ts,
+            unlocked: now >= lock.unlock_ts,
+            released: lock.released,
+        })
+    }
+
+    // ============= ALLOCATION BUCKETS =============
+
+    /// Creates the bucket for one tokenomics category. The caps of all
+    /// buckets together may not exceed `max_supply`.
+    pub fn create_allocation_bucket(
+        ctx: Context<CreateAllocationBucket>,
+        category: BucketCategory,
+        cap: u64,
+        destination: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+
+        let token_info = &mut ctx.accounts.token_info;
+        let bucket_caps_total = token_info
+            .bucket_caps_total
+            .checked_add(cap)
+            .ok_or(TokenError::MathOverflow)?;
+        require!(
+            bucket_caps_total <= token_info.max_supply,
+            TokenError::BucketCapsExceedMaxSupply
+        );
+        token_info.bucket_caps_total = bucket_caps_total;
+
+        let bucket = &mut ctx.accounts.bucket;
+        bucket.mint = ctx.accounts.mint.key();
+        bucket.category = category;
+        bucket.cap = cap;
+        bucket.destination = destination;
+        bucket.minted = 0;
+        bucket.released = 0;
+        bucket.bump = ctx.bumps.bucket;
+
+        emit!(AllocationBucketCreated {
+            mint: bucket.mint,
+            category,
+            cap,
+            destination,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
 
-        token::transfer(cpi_ctx, amount)?;
+    /// Unlocks another `amount` of a bucket's cap for minting.
+    pub fn release_allocation_bucket(
+        ctx: Context<ReleaseAllocationBucket>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
 
-        emit!(LiquidityDrained {
-            from: ctx.accounts.liquidity_pool.key(),
-            to: ctx.accounts.treasury.key(),
+        let bucket = &mut ctx.accounts.bucket;
+        let released = bucket
+            .released
+            .checked_add(amount)
+            .ok_or(TokenError::MathOverflow)?;
+        require!(released <= bucket.cap, TokenError::BucketCapExceeded);
+        bucket.released = released;
+
+        emit!(AllocationBucketReleased {
+            mint: bucket.mint,
+            category: bucket.category,
             amount,
+            released,
             authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
         });
 
         Ok(())
     }
 
+    /// Returns every created bucket. `remaining_accounts` must hold the bucket
+    /// PDA of each category in `BucketCategory::ALL` order; categories without
+    /// a bucket are skipped.
+    pub fn get_allocation_buckets(ctx: Context<ViewAllocationBuckets>) -> Result<Vec<BucketSummary>> {
+        require!(
+            ctx.remaining_accounts.len() == BucketCategory::ALL.len(),
+            TokenError::BatchLengthMismatch
+        );
+
+        let mint = ctx.accounts.mint.key();
+        let mut buckets = Vec::with_capacity(BucketCategory::ALL.len());
+        for (category, account) in BucketCategory::ALL.iter().zip(ctx.remaining_accounts.iter()) {
+            verify_compliance_pda(account, &[BUCKET_SEED, mint.as_ref(), &[*category as u8]])?;
+            if let Some(bucket) = load_compliance_entry::<AllocationBucket>(account)? {
+                buckets.push(BucketSummary {
+                    category: bucket.category,
+                    cap: bucket.cap,
+                    destination: bucket.destination,
+                    minted: bucket.minted,
+                    released: bucket.released,
+                });
+            }
+        }
+
+        Ok(buckets)
+    }
+
     // ============= BLACKLIST FUNCTIONS =============
     
+    /// Blacklists `wallet`, or replaces its existing entry. An entry with
+    /// `expires_at` stops blocking once that time passes.
     pub fn add_to_blacklist(
         ctx: Context<ManageBlacklist>,
         wallet: Pubkey,
+        reason_code: u8,
+        reason: String,
+        expires_at: Option<i64>,
+        reference_hash: [u8; 32],
     ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
         require!(
-            ctx.accounts.authority.key() == ctx.accounts.token_info.authority,
-            TokenError::UnauthorizedAccess
+            reason.len() <= MAX_BLACKLIST_REASON_LEN,
+            TokenError::BlacklistReasonTooLong
         );
+        let now = Clock::get()?.unix_timestamp;
+        if let Some(expires_at) = expires_at {
+            require!(expires_at > now, TokenError::InvalidBlacklistExpiry);
+        }
+
+        // Entries written before reason codes existed are smaller
+        grow_account(
+            &ctx.accounts.blacklist.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + BlacklistEntry::INIT_SPACE,
+        )?;
 
         let blacklist = &mut ctx.accounts.blacklist;
         blacklist.wallet = wallet;
         blacklist.is_blacklisted = true;
-        blacklist.added_at = Clock::get()?.unix_timestamp;
-        blacklist.reason = String::from("Admin blocked");
+        blacklist.added_at = now;
+        blacklist.reason = reason;
+        blacklist.reason_code = reason_code;
+        blacklist.expires_at = expires_at;
+        blacklist.reference_hash = reference_hash;
+        blacklist.appeal = AppealStatus::None;
 
         emit!(WalletBlacklisted {
             wallet,
             authority: ctx.accounts.authority.key(),
-            timestamp: blacklist.added_at,
+            timestamp: now,
+            reason_code,
+            reason: blacklist.reason.clone(),
+            expires_at,
+            reference_hash,
         });
 
         Ok(())
@@ -156,13 +656,11 @@ pub mod dollar_token {
         ctx: Context<RemoveBlacklist>,
         _wallet: Pubkey,
     ) -> Result<()> {
-        require!(
-            ctx.accounts.authority.key() == ctx.accounts.token_info.authority,
-            TokenError::UnauthorizedAccess
-        );
+        ctx.accounts.role_grant.check_active()?;
 
         let blacklist = &mut ctx.accounts.blacklist;
         blacklist.is_blacklisted = false;
+        blacklist.appeal = AppealStatus::None;
 
         emit!(WalletUnblacklisted {
             wallet: blacklist.wallet,
@@ -173,14 +671,32 @@ pub mod dollar_token {
         Ok(())
     }
 
+    /// Deletes a blacklist entry and returns its rent. A closed entry reads
+    /// as "not listed"; the event records the state it had when closed.
+    pub fn close_blacklist_entry(
+        ctx: Context<CloseBlacklistEntry>,
+        _wallet: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+
+        let blacklist = &ctx.accounts.blacklist;
+        emit!(BlacklistEntryClosed {
+            wallet: blacklist.wallet,
+            was_blacklisted: blacklist.is_blacklisted,
+            reason_code: blacklist.reason_code,
+            rent_receiver: ctx.accounts.rent_receiver.key(),
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
     pub fn toggle_blacklist_system(
         ctx: Context<ToggleBlacklistSystem>,
         enabled: bool,
     ) -> Result<()> {
-        require!(
-            ctx.accounts.authority.key() == ctx.accounts.token_info.authority,
-            TokenError::UnauthorizedAccess
-        );
+        ctx.accounts.role_grant.check_active()?;
 
         let token_info = &mut ctx.accounts.token_info;
         token_info.blacklist_enabled = enabled;
@@ -194,6 +710,112 @@ pub mod dollar_token {
         Ok(())
     }
 
+    // ============= BLACKLIST APPEALS =============
+
+    /// Lets a blacklisted wallet contest its entry. The compliance team must
+    /// resolve the appeal within `TokenInfo.appeal_window`, otherwise the
+    /// entry lapses. The evidence itself lives off-chain; its hash is kept.
+    pub fn file_appeal(
+        ctx: Context<FileAppeal>,
+        evidence_uri: String,
+        evidence_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(evidence_uri.len() <= MAX_URI_LEN, TokenError::EvidenceUriTooLong);
+        let appeal_window = ctx.accounts.token_info.appeal_window;
+        require!(appeal_window > 0, TokenError::InvalidAppealWindow);
+
+        let now = Clock::get()?.unix_timestamp;
+        let blacklist = &ctx.accounts.blacklist;
+        require!(blacklist.is_active(now), TokenError::NotBlacklisted);
+        require!(
+            blacklist.appeal == AppealStatus::None,
+            TokenError::AppealAlreadyFiled
+        );
+
+        grow_account(
+            &ctx.accounts.blacklist.to_account_info(),
+            &ctx.accounts.wallet.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + BlacklistEntry::INIT_SPACE,
+        )?;
+
+        let deadline = now
+            .checked_add(appeal_window)
+            .ok_or(TokenError::MathOverflow)?;
+        let blacklist = &mut ctx.accounts.blacklist;
+        blacklist.appeal = AppealStatus::Pending {
+            deadline,
+            evidence_hash,
+        };
+
+        emit!(AppealFiled {
+            wallet: blacklist.wallet,
+            evidence_uri,
+            evidence_hash,
+            deadline,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Upholds or lifts a pending appeal. Only possible before its deadline;
+    /// afterwards the entry has already lapsed.
+    pub fn resolve_appeal(
+        ctx: Context<ResolveAppeal>,
+        _wallet: Pubkey,
+        uphold: bool,
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let blacklist = &mut ctx.accounts.blacklist;
+        match blacklist.appeal {
+            AppealStatus::Pending { deadline, .. } => {
+                require!(now <= deadline, TokenError::AppealWindowElapsed);
+            }
+            _ => return Err(TokenError::NoPendingAppeal.into()),
+        }
+
+        if uphold {
+            blacklist.appeal = AppealStatus::Upheld;
+        } else {
+            blacklist.is_blacklisted = false;
+            blacklist.appeal = AppealStatus::None;
+        }
+
+        emit!(AppealResolved {
+            wallet: blacklist.wallet,
+            upheld: uphold,
+            authority: ctx.accounts.authority.key(),
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_appeal_window(
+        ctx: Context<SetAppealWindow>,
+        appeal_window: i64,
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        require!(
+            appeal_window > 0 && appeal_window <= MAX_APPEAL_WINDOW,
+            TokenError::InvalidAppealWindow
+        );
+
+        let token_info = &mut ctx.accounts.token_info;
+        token_info.appeal_window = appeal_window;
+
+        emit!(AppealWindowUpdated {
+            mint: token_info.mint,
+            appeal_window,
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
     // ============= WHITELIST FUNCTIONS =============
     
     pub fn add_to_whitelist(
@@ -201,10 +823,7 @@ pub mod dollar_token {
         wallet: Pubkey,
         allocation: u64,
     ) -> Result<()> {
-        require!(
-            ctx.accounts.authority.key() == ctx.accounts.token_info.authority,
-            TokenError::UnauthorizedAccess
-        );
+        ctx.accounts.role_grant.check_active()?;
 
         let whitelist = &mut ctx.accounts.whitelist;
         whitelist.wallet = wallet;
@@ -227,10 +846,7 @@ pub mod dollar_token {
         ctx: Context<RemoveWhitelist>,
         _wallet: Pubkey,
     ) -> Result<()> {
-        require!(
-            ctx.accounts.authority.key() == ctx.accounts.token_info.authority,
-            TokenError::UnauthorizedAccess
-        );
+        ctx.accounts.role_grant.check_active()?;
 
         let whitelist = &mut ctx.accounts.whitelist;
         whitelist.is_whitelisted = false;
@@ -244,20 +860,21 @@ pub mod dollar_token {
         Ok(())
     }
 
-    pub fn toggle_whitelist_mode(
-        ctx: Context<ToggleWhitelistMode>,
-        enabled: bool,
+    /// Deletes a whitelist entry and returns its rent. Re-adding the wallet
+    /// later starts a fresh allocation.
+    pub fn close_whitelist_entry(
+        ctx: Context<CloseWhitelistEntry>,
+        _wallet: Pubkey,
     ) -> Result<()> {
-        require!(
-            ctx.accounts.authority.key() == ctx.accounts.token_info.authority,
-            TokenError::UnauthorizedAccess
-        );
-
-        let token_info = &mut ctx.accounts.token_info;
-        token_info.whitelist_enabled = enabled;
+        ctx.accounts.role_grant.check_active()?;
 
-        emit!(WhitelistModeToggled {
-            enabled,
+        let whitelist = &ctx.accounts.whitelist;
+        emit!(WhitelistEntryClosed {
+            wallet: whitelist.wallet,
+            was_whitelisted: whitelist.is_whitelisted,
+            allocation: whitelist.allocation,
+            purchased: whitelist.purchased,
+            rent_receiver: ctx.accounts.rent_receiver.key(),
             authority: ctx.accounts.authority.key(),
             timestamp: Clock::get()?.unix_timestamp,
         });
@@ -265,22 +882,25 @@ pub mod dollar_token {
         Ok(())
     }
 
-    // ============= TRADING CONTROL =============
-    
-    pub fn toggle_trading(
-        ctx: Context<ToggleTrading>,
-        enabled: bool,
+    /// Adds `amount` to a wallet's allocation, keeping what it already
+    /// received.
+    pub fn top_up_allocation(
+        ctx: Context<ManageAllocation>,
+        _wallet: Pubkey,
+        amount: u64,
     ) -> Result<()> {
-        require!(
-            ctx.accounts.authority.key() == ctx.accounts.token_info.authority,
-            TokenError::UnauthorizedAccess
-        );
+        ctx.accounts.role_grant.check_active()?;
 
-        let token_info = &mut ctx.accounts.token_info;
-        token_info.trading_enabled = enabled;
+        let whitelist = &mut ctx.accounts.whitelist;
+        whitelist.allocation = whitelist
+            .allocation
+            .checked_add(amount)
+            .ok_or(TokenError::MathOverflow)?;
 
-        emit!(TradingToggled {
-            enabled,
+        emit!(WhitelistAllocationUpdated {
+            wallet: whitelist.wallet,
+            allocation: whitelist.allocation,
+            purchased: whitelist.purchased,
             authority: ctx.accounts.authority.key(),
             timestamp: Clock::get()?.unix_timestamp,
         });
@@ -288,85 +908,698 @@ pub mod dollar_token {
         Ok(())
     }
 
-    // ============= TRANSFER WITH CHECKS =============
+    /// Starts a fresh allocation round: sets the allocation and clears what
+    /// the wallet has received so far.
+    pub fn reset_allocation(
+        ctx: Context<ManageAllocation>,
+        _wallet: Pubkey,
+        allocation: u64,
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+
+        let whitelist = &mut ctx.accounts.whitelist;
+        whitelist.allocation = allocation;
+        whitelist.purchased = 0;
+
+        emit!(WhitelistAllocationUpdated {
+            wallet: whitelist.wallet,
+            allocation,
+            purchased: 0,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn toggle_whitelist_mode(
+        ctx: Context<ToggleWhitelistMode>,
+        enabled: bool,
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+
+        let token_info = &mut ctx.accounts.token_info;
+        token_info.whitelist_enabled = enabled;
+
+        emit!(WhitelistModeToggled {
+            enabled,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ============= TRADING CONTROL =============
     
-    pub fn controlled_transfer(
-        ctx: Context<ControlledTransfer>,
-        amount: u64,
+    pub fn toggle_trading(
+        ctx: Context<ToggleTrading>,
+        enabled: bool,
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        require!(
+            ctx.accounts.token_info.timelock_delays.get(TimelockKind::ToggleTrading) == 0,
+            TokenError::TimelockRequired
+        );
+
+        set_trading_enabled(
+            &mut ctx.accounts.token_info,
+            enabled,
+            ctx.accounts.authority.key(),
+        )
+    }
+
+    // ============= ACCOUNT FREEZE =============
+
+    /// Freezes a single token account through the SPL freeze authority held
+    /// by the mint authority PDA, leaving everyone else able to trade.
+    pub fn freeze_account(ctx: Context<FreezeTokenAccount>, reason_code: u8) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        require!(!ctx.accounts.account.is_frozen(), TokenError::AccountAlreadyFrozen);
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            MINT_AUTHORITY_SEED,
+            mint_key.as_ref(),
+            &[ctx.accounts.token_info.mint_authority_bump],
+        ];
+        let cpi_accounts = FreezeAccount {
+            account: ctx.accounts.account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        token_interface::freeze_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            &[&seeds[..]],
+        ))?;
+
+        let token_info = &mut ctx.accounts.token_info;
+        token_info.frozen_accounts = token_info
+            .frozen_accounts
+            .checked_add(1)
+            .ok_or(TokenError::MathOverflow)?;
+
+        emit!(AccountFrozen {
+            mint: mint_key,
+            account: ctx.accounts.account.key(),
+            owner: ctx.accounts.account.owner,
+            reason_code,
+            frozen_accounts: token_info.frozen_accounts,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn thaw_account(ctx: Context<FreezeTokenAccount>, reason_code: u8) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        require!(ctx.accounts.account.is_frozen(), TokenError::AccountNotFrozen);
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            MINT_AUTHORITY_SEED,
+            mint_key.as_ref(),
+            &[ctx.accounts.token_info.mint_authority_bump],
+        ];
+        let cpi_accounts = ThawAccount {
+            account: ctx.accounts.account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        token_interface::thaw_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            &[&seeds[..]],
+        ))?;
+
+        let token_info = &mut ctx.accounts.token_info;
+        token_info.frozen_accounts = token_info
+            .frozen_accounts
+            .checked_sub(1)
+            .ok_or(TokenError::FrozenAccountsUnderflow)?;
+
+        emit!(AccountThawed {
+            mint: mint_key,
+            account: ctx.accounts.account.key(),
+            owner: ctx.accounts.account.owner,
+            reason_code,
+            frozen_accounts: token_info.frozen_accounts,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ============= TRANSFER LIMITS =============
+
+    /// Loosens the anti-whale limits immediately. `None` removes a limit;
+    /// adding or lowering one has to go through the queue as
+    /// `TimelockedAction::SetTransferLimits`.
+    pub fn raise_transfer_limits(
+        ctx: Context<UpdateTransferLimits>,
+        max_tx_amount: Option<u64>,
+        max_wallet_balance: Option<u64>,
     ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
         let token_info = &ctx.accounts.token_info;
-        
-        // Check if trading is enabled
         require!(
-            token_info.trading_enabled,
-            TokenError::TradingDisabled
+            is_limit_raise(token_info.max_tx_amount, max_tx_amount)
+                && is_limit_raise(token_info.max_wallet_balance, max_wallet_balance),
+            TokenError::TransferLimitDecreaseRequiresQueue
         );
 
-        // Check blacklist for sender (if account provided)
-        if token_info.blacklist_enabled {
-            if let Some(sender_blacklist) = &ctx.accounts.sender_blacklist {
-                require!(
-                    !sender_blacklist.is_blacklisted,
-                    TokenError::SenderBlacklisted
-                );
-            }
+        set_transfer_limits(
+            &mut ctx.accounts.token_info,
+            max_tx_amount,
+            max_wallet_balance,
+            ctx.accounts.authority.key(),
+        )
+    }
+
+    /// Exempts a token account (treasury, presale vault, pool) from the
+    /// transfer limits.
+    pub fn add_limit_exemption(ctx: Context<AddLimitExemption>) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let exemption = &mut ctx.accounts.exemption;
+        exemption.mint = ctx.accounts.mint.key();
+        exemption.account = ctx.accounts.account.key();
+        exemption.added_by = ctx.accounts.authority.key();
+        exemption.added_at = now;
+        exemption.bump = ctx.bumps.exemption;
+
+        emit!(LimitExemptionAdded {
+            mint: exemption.mint,
+            account: exemption.account,
+            authority: exemption.added_by,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Loosens the 24-hour outflow limits immediately, like
+    /// `raise_transfer_limits`. Adding or lowering one is queued as
+    /// `TimelockedAction::SetVelocityLimits`.
+    pub fn raise_velocity_limits(
+        ctx: Context<UpdateTransferLimits>,
+        velocity_limit: Option<u64>,
+        whitelisted_velocity_limit: Option<u64>,
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        let token_info = &ctx.accounts.token_info;
+        require!(
+            is_limit_raise(token_info.velocity_limit, velocity_limit)
+                && is_limit_raise(token_info.whitelisted_velocity_limit, whitelisted_velocity_limit),
+            TokenError::TransferLimitDecreaseRequiresQueue
+        );
+
+        set_velocity_limits(
+            &mut ctx.accounts.token_info,
+            velocity_limit,
+            whitelisted_velocity_limit,
+            ctx.accounts.authority.key(),
+        )
+    }
+
+    pub fn remove_limit_exemption(ctx: Context<RemoveLimitExemption>) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+
+        emit!(LimitExemptionRemoved {
+            mint: ctx.accounts.exemption.mint,
+            account: ctx.accounts.exemption.account,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ============= TRANSFER FEES =============
+
+    /// Sets the fee `controlled_transfer` charges, in basis points capped at
+    /// `maximum_fee` per transfer, and the token account it is paid into. On
+    /// a Token-2022 mint the rate is also pushed to the transfer fee
+    /// extension, which applies it two epochs later.
+    pub fn configure_transfer_fee(
+        ctx: Context<ConfigureTransferFee>,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        validate_transfer_fee(transfer_fee_basis_points)?;
+
+        let mint = ctx.accounts.mint.to_account_info();
+        if mint.owner == &Token2022::id() {
+            let mint_key = mint.key();
+            let seeds = &[
+                MINT_AUTHORITY_SEED,
+                mint_key.as_ref(),
+                &[ctx.accounts.token_info.mint_authority_bump],
+            ];
+            let cpi_accounts = TransferFeeSetTransferFee {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                mint,
+                authority: ctx.accounts.mint_authority.to_account_info(),
+            };
+            transfer_fee_set(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    &[&seeds[..]],
+                ),
+                transfer_fee_basis_points,
+                maximum_fee,
+            )?;
         }
 
-        // Check blacklist for recipient (if account provided)
-        if token_info.blacklist_enabled {
-            if let Some(recipient_blacklist) = &ctx.accounts.recipient_blacklist {
-                require!(
-                    !recipient_blacklist.is_blacklisted,
-                    TokenError::RecipientBlacklisted
-                );
-            }
+        let token_info = &mut ctx.accounts.token_info;
+        token_info.transfer_fee_basis_points = transfer_fee_basis_points;
+        token_info.maximum_fee = maximum_fee;
+        token_info.fee_collector = ctx.accounts.fee_collector.key();
+
+        emit!(TransferFeeConfigured {
+            mint: token_info.mint,
+            transfer_fee_basis_points,
+            maximum_fee,
+            fee_collector: token_info.fee_collector,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Exempts a token account (treasury, presale vault, pool) from the
+    /// `controlled_transfer` fee.
+    pub fn add_fee_exemption(ctx: Context<AddFeeExemption>) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let exemption = &mut ctx.accounts.exemption;
+        exemption.mint = ctx.accounts.mint.key();
+        exemption.account = ctx.accounts.account.key();
+        exemption.added_by = ctx.accounts.authority.key();
+        exemption.added_at = now;
+        exemption.bump = ctx.bumps.exemption;
+
+        emit!(FeeExemptionAdded {
+            mint: exemption.mint,
+            account: exemption.account,
+            authority: exemption.added_by,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn remove_fee_exemption(ctx: Context<RemoveFeeExemption>) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+
+        emit!(FeeExemptionRemoved {
+            mint: ctx.accounts.exemption.mint,
+            account: ctx.accounts.exemption.account,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Sweeps the fees withheld in the token accounts passed in
+    /// `remaining_accounts` into the Token-2022 mint. Needs no signature, so
+    /// anyone can crank it.
+    pub fn harvest_withheld<'info>(
+        ctx: Context<'_, '_, 'info, 'info, HarvestWithheld<'info>>,
+    ) -> Result<()> {
+        let cpi_accounts = HarvestWithheldTokensToMint {
+            token_program_id: ctx.accounts.token_program.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+        };
+        harvest_withheld_tokens_to_mint(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            ctx.remaining_accounts.to_vec(),
+        )?;
+
+        emit!(WithheldFeesHarvested {
+            mint: ctx.accounts.mint.key(),
+            accounts: ctx.remaining_accounts.len() as u32,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Pays the fees harvested into the Token-2022 mint out to the fee
+    /// collector. The mint authority PDA holds the withdraw authority.
+    pub fn withdraw_withheld(ctx: Context<WithdrawWithheld>) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        let amount = withheld_fees(&ctx.accounts.mint.to_account_info())?;
+        require!(amount > 0, TokenError::NoWithheldFees);
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            MINT_AUTHORITY_SEED,
+            mint_key.as_ref(),
+            &[ctx.accounts.token_info.mint_authority_bump],
+        ];
+        let cpi_accounts = WithdrawWithheldTokensFromMint {
+            token_program_id: ctx.accounts.token_program.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            destination: ctx.accounts.fee_collector.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        withdraw_withheld_tokens_from_mint(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            &[&seeds[..]],
+        ))?;
+
+        emit!(WithheldFeesWithdrawn {
+            mint: mint_key,
+            destination: ctx.accounts.fee_collector.key(),
+            amount,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ============= BATCH COMPLIANCE =============
+
+    /// Whitelists `wallets[i]` with `allocations[i]`. The matching entry PDAs
+    /// are passed in `remaining_accounts`, in the same order, and created
+    /// when missing.
+    pub fn batch_add_to_whitelist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchManageCompliance<'info>>,
+        wallets: Vec<Pubkey>,
+        allocations: Vec<u64>,
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        check_batch_len(wallets.len(), ctx.remaining_accounts.len())?;
+        require!(
+            allocations.len() == wallets.len(),
+            TokenError::BatchLengthMismatch
+        );
+
+        let mint = ctx.accounts.mint.key();
+        let now = Clock::get()?.unix_timestamp;
+        for ((wallet, allocation), account) in wallets
+            .iter()
+            .zip(allocations.iter())
+            .zip(ctx.remaining_accounts.iter())
+        {
+            open_compliance_entry(
+                account,
+                &[b"whitelist", mint.as_ref(), wallet.as_ref()],
+                8 + WhitelistEntry::INIT_SPACE,
+                &ctx.accounts.authority.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+            )?;
+            let entry = WhitelistEntry {
+                wallet: *wallet,
+                is_whitelisted: true,
+                allocation: *allocation,
+                purchased: 0,
+                added_at: now,
+            };
+            entry.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
         }
 
-        // Check whitelist mode
-        if token_info.whitelist_enabled {
-            // In whitelist mode, sender must be whitelisted
-            if let Some(sender_whitelist) = &ctx.accounts.sender_whitelist {
-                require!(
-                    sender_whitelist.is_whitelisted,
-                    TokenError::SenderNotWhitelisted
-                );
-            } else {
-                return Err(TokenError::SenderNotWhitelisted.into());
-            }
+        emit!(WhitelistBatchAdded {
+            wallets,
+            allocations,
+            authority: ctx.accounts.authority.key(),
+            timestamp: now,
+        });
 
-            // Recipient must be whitelisted
-            if let Some(recipient_whitelist) = &ctx.accounts.recipient_whitelist {
-                require!(
-                    recipient_whitelist.is_whitelisted,
-                    TokenError::RecipientNotWhitelisted
-                );
-            } else {
-                return Err(TokenError::RecipientNotWhitelisted.into());
-            }
+        Ok(())
+    }
+
+    pub fn batch_remove_from_whitelist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchManageCompliance<'info>>,
+        wallets: Vec<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        check_batch_len(wallets.len(), ctx.remaining_accounts.len())?;
+
+        let mint = ctx.accounts.mint.key();
+        for (wallet, account) in wallets.iter().zip(ctx.remaining_accounts.iter()) {
+            verify_compliance_pda(account, &[b"whitelist", mint.as_ref(), wallet.as_ref()])?;
+            let mut entry = load_compliance_entry::<WhitelistEntry>(account)?
+                .ok_or(ErrorCode::AccountNotInitialized)?;
+            entry.is_whitelisted = false;
+            entry.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
         }
 
-        // Execute transfer
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.from.to_account_info(),
-            to: ctx.accounts.to.to_account_info(),
-            authority: ctx.accounts.authority.to_account_info(),
+        emit!(WhitelistBatchRemoved {
+            wallets,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Blacklists every wallet in `wallets` under the same reason, expiry
+    /// and case reference. Entry PDAs go in `remaining_accounts`.
+    pub fn batch_add_to_blacklist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchManageCompliance<'info>>,
+        wallets: Vec<Pubkey>,
+        reason_code: u8,
+        reason: String,
+        expires_at: Option<i64>,
+        reference_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        check_batch_len(wallets.len(), ctx.remaining_accounts.len())?;
+        require!(
+            reason.len() <= MAX_BLACKLIST_REASON_LEN,
+            TokenError::BlacklistReasonTooLong
+        );
+        let now = Clock::get()?.unix_timestamp;
+        if let Some(expires_at) = expires_at {
+            require!(expires_at > now, TokenError::InvalidBlacklistExpiry);
+        }
+
+        let mint = ctx.accounts.mint.key();
+        for (wallet, account) in wallets.iter().zip(ctx.remaining_accounts.iter()) {
+            open_compliance_entry(
+                account,
+                &[b"blacklist", mint.as_ref(), wallet.as_ref()],
+                8 + BlacklistEntry::INIT_SPACE,
+                &ctx.accounts.authority.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+            )?;
+            let entry = BlacklistEntry {
+                wallet: *wallet,
+                is_blacklisted: true,
+                added_at: now,
+                reason: reason.clone(),
+                reason_code,
+                expires_at,
+                reference_hash,
+                appeal: AppealStatus::None,
+            };
+            entry.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+        }
+
+        emit!(BlacklistBatchAdded {
+            wallets,
+            reason_code,
+            reason,
+            expires_at,
+            reference_hash,
+            authority: ctx.accounts.authority.key(),
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn batch_remove_from_blacklist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchManageCompliance<'info>>,
+        wallets: Vec<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        check_batch_len(wallets.len(), ctx.remaining_accounts.len())?;
+
+        let mint = ctx.accounts.mint.key();
+        for (wallet, account) in wallets.iter().zip(ctx.remaining_accounts.iter()) {
+            verify_compliance_pda(account, &[b"blacklist", mint.as_ref(), wallet.as_ref()])?;
+            let mut entry = load_compliance_entry::<BlacklistEntry>(account)?
+                .ok_or(ErrorCode::AccountNotInitialized)?;
+            entry.is_blacklisted = false;
+            entry.appeal = AppealStatus::None;
+            entry.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+        }
+
+        emit!(BlacklistBatchRemoved {
+            wallets,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ============= MERKLE LISTS =============
+
+    /// Publishes a new whitelist root. Wallets proven against it count as
+    /// whitelisted in `controlled_transfer`, without an allocation. A zero
+    /// root turns the Merkle whitelist off.
+    pub fn set_whitelist_root(ctx: Context<SetListRoot>, root: [u8; 32]) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        require_merkle_lists_supported(&ctx.accounts.mint.to_account_info())?;
+
+        let token_info = &mut ctx.accounts.token_info;
+        token_info.whitelist_root = root;
+        token_info.whitelist_root_version = token_info
+            .whitelist_root_version
+            .checked_add(1)
+            .ok_or(TokenError::MathOverflow)?;
+
+        emit!(ListRootUpdated {
+            mint: token_info.mint,
+            blacklist: false,
+            root,
+            version: token_info.whitelist_root_version,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Publishes a new blacklist root over the sorted list of blocked wallets.
+    /// While set, both sides of `controlled_transfer` must prove they fall
+    /// between two adjacent leaves. A zero root turns it off.
+    pub fn set_blacklist_root(ctx: Context<SetListRoot>, root: [u8; 32]) -> Result<()> {
+        ctx.accounts.role_grant.check_active()?;
+        require_merkle_lists_supported(&ctx.accounts.mint.to_account_info())?;
+
+        let token_info = &mut ctx.accounts.token_info;
+        token_info.blacklist_root = root;
+        token_info.blacklist_root_version = token_info
+            .blacklist_root_version
+            .checked_add(1)
+            .ok_or(TokenError::MathOverflow)?;
+
+        emit!(ListRootUpdated {
+            mint: token_info.mint,
+            blacklist: true,
+            root,
+            version: token_info.blacklist_root_version,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ============= TRANSFER WITH CHECKS =============
+    
+    pub fn controlled_transfer(
+        ctx: Context<ControlledTransfer>,
+        amount: u64,
+        proofs: TransferProofs,
+    ) -> Result<()> {
+        let mint = ctx.accounts.mint.to_account_info();
+        require!(mint.owner != &Token2022::id(), TokenError::Token2022TransferUnsupported);
+
+        let token_info = &ctx.accounts.token_info;
+        let witness = MerkleWitness {
+            sender: ctx.accounts.from.owner,
+            recipient: ctx.accounts.to.owner,
+            proofs: &proofs,
         };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        let sender_whitelist = load_compliance_entry::<WhitelistEntry>(&ctx.accounts.sender_whitelist)?;
+        check_transfer_policy(
+            token_info,
+            load_compliance_entry::<BlacklistEntry>(&ctx.accounts.sender_blacklist)?.as_ref(),
+            load_compliance_entry::<BlacklistEntry>(&ctx.accounts.recipient_blacklist)?.as_ref(),
+            sender_whitelist.as_ref(),
+            load_compliance_entry::<WhitelistEntry>(&ctx.accounts.recipient_whitelist)?.as_ref(),
+            Some(&witness),
+        )?;
+        let fee_exempt = load_compliance_entry::<TransferFeeExemption>(&ctx.accounts.sender_fee_exemption)?.is_some()
+            || load_compliance_entry::<TransferFeeExemption>(&ctx.accounts.recipient_fee_exemption)?.is_some();
+        let fee = calculate_transfer_fee(token_info, amount, fee_exempt)?;
+        let net = amount.checked_sub(fee).ok_or(TokenError::MathOverflow)?;
+        let sender_limit_exempt = load_compliance_entry::<TransferLimitExemption>(&ctx.accounts.sender_limit_exemption)?.is_some();
+        check_transfer_limits(
+            token_info,
+            amount,
+            net,
+            ctx.accounts.to.amount,
+            sender_limit_exempt,
+            load_compliance_entry::<TransferLimitExemption>(&ctx.accounts.recipient_limit_exemption)?.is_some(),
+        )?;
+        let sender_whitelisted = is_sender_whitelisted(token_info, sender_whitelist.as_ref(), &witness);
+        if let (false, Some(limit)) = (sender_limit_exempt, velocity_limit(token_info, sender_whitelisted)) {
+            record_velocity(
+                token_info,
+                &ctx.accounts.sender_velocity,
+                ctx.accounts.from.owner,
+                amount,
+                limit,
+                &ctx.accounts.authority.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+            )?;
+        }
+        record_whitelist_receipt(token_info, &ctx.accounts.recipient_whitelist, net)?;
 
-        token::transfer(cpi_ctx, amount)?;
+        transfer_from_sender(ctx.accounts, ctx.accounts.to.to_account_info(), net)?;
+        if fee > 0 {
+            let fee_collector = ctx
+                .accounts
+                .fee_collector
+                .as_ref()
+                .ok_or(TokenError::FeeCollectorMismatch)?;
+            transfer_from_sender(ctx.accounts, fee_collector.to_account_info(), fee)?;
+        }
 
         emit!(ControlledTransferExecuted {
             from: ctx.accounts.from.key(),
             to: ctx.accounts.to.key(),
-            amount,
+            gross: amount,
+            fee,
+            net,
             authority: ctx.accounts.authority.key(),
         });
 
         Ok(())
     }
 
-    pub fn transfer_authority(
+    /// Token-2022 transfer hook for mints created by `initialize_token_2022`.
+    /// Runs the same checks as `controlled_transfer` on every transfer.
+    /// Whitelist receipts count what the recipient keeps after the fee the
+    /// transfer fee extension withholds.
+    #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        assert_is_transferring(&ctx.accounts.source_token.to_account_info())?;
+
+        check_transfer_policy(
+            &ctx.accounts.token_info,
+            load_compliance_entry::<BlacklistEntry>(&ctx.accounts.sender_blacklist)?.as_ref(),
+            load_compliance_entry::<BlacklistEntry>(&ctx.accounts.recipient_blacklist)?.as_ref(),
+            load_compliance_entry::<WhitelistEntry>(&ctx.accounts.sender_whitelist)?.as_ref(),
+            load_compliance_entry::<WhitelistEntry>(&ctx.accounts.recipient_whitelist)?.as_ref(),
+            None,
+        )?;
+        let fee = withheld_transfer_fee(&ctx.accounts.mint.to_account_info(), amount)?;
+        let net = amount.checked_sub(fee).ok_or(TokenError::MathOverflow)?;
+        record_whitelist_receipt(&ctx.accounts.token_info, &ctx.accounts.recipient_whitelist, net)
+    }
+
+    // ============= AUTHORITY HANDOVER =============
+
+    pub fn propose_authority(
         ctx: Context<TransferAuthority>,
         new_authority: Pubkey,
     ) -> Result<()> {
@@ -374,24 +1607,819 @@ pub mod dollar_token {
             ctx.accounts.authority.key() == ctx.accounts.token_info.authority,
             TokenError::UnauthorizedAccess
         );
+        require!(
+            ctx.accounts.token_info.timelock_delays.get(TimelockKind::TransferAuthority) == 0,
+            TokenError::TimelockRequired
+        );
+
+        set_pending_authority(&mut ctx.accounts.token_info, new_authority)
+    }
 
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
         let token_info = &mut ctx.accounts.token_info;
+        let pending_authority = token_info
+            .pending_authority
+            .ok_or(TokenError::NoPendingAuthority)?;
+        require_keys_eq!(
+            ctx.accounts.new_authority.key(),
+            pending_authority,
+            TokenError::InvalidPendingAuthority
+        );
+
         let old_authority = token_info.authority;
-        token_info.authority = new_authority;
+        token_info.authority = pending_authority;
+        token_info.pending_authority = None;
 
         emit!(AuthorityTransferred {
             old_authority,
-            new_authority,
+            new_authority: pending_authority,
             mint: ctx.accounts.mint.key(),
         });
 
         Ok(())
     }
-}
-
-// ============= ACCOUNT STRUCTS =============
+
+    pub fn cancel_authority_proposal(ctx: Context<TransferAuthority>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.token_info.authority,
+            TokenError::UnauthorizedAccess
+        );
+
+        let token_info = &mut ctx.accounts.token_info;
+        let cancelled_authority = token_info
+            .pending_authority
+            .take()
+            .ok_or(TokenError::NoPendingAuthority)?;
+
+        emit!(AuthorityProposalCancelled {
+            current_authority: token_info.authority,
+            cancelled_authority,
+            mint: ctx.accounts.mint.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ============= ROLES =============
+
+    /// The token authority manages every role; holders of `RoleAdmin` manage
+    /// all roles except `RoleAdmin` itself.
+    pub fn grant_role(
+        ctx: Context<GrantRole>,
+        role: Role,
+        holder: Pubkey,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        authorize_role_admin(
+            &ctx.accounts.token_info,
+            ctx.accounts.admin.key(),
+            ctx.accounts.admin_grant.as_ref(),
+            role,
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        if let Some(expires_at) = expires_at {
+            require!(expires_at > now, TokenError::InvalidRoleExpiry);
+        }
+
+        let role_grant = &mut ctx.accounts.role_grant;
+        role_grant.mint = ctx.accounts.mint.key();
+        role_grant.holder = holder;
+        role_grant.role = role;
+        role_grant.granted_by = ctx.accounts.admin.key();
+        role_grant.granted_at = now;
+        role_grant.expires_at = expires_at;
+        role_grant.bump = ctx.bumps.role_grant;
+
+        emit!(RoleGranted {
+            mint: role_grant.mint,
+            role,
+            holder,
+            expires_at,
+            granted_by: role_grant.granted_by,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn revoke_role(ctx: Context<RevokeRole>) -> Result<()> {
+        let role_grant = &ctx.accounts.role_grant;
+        authorize_role_admin(
+            &ctx.accounts.token_info,
+            ctx.accounts.admin.key(),
+            ctx.accounts.admin_grant.as_ref(),
+            role_grant.role,
+        )?;
+
+        emit!(RoleRevoked {
+            mint: role_grant.mint,
+            role: role_grant.role,
+            holder: role_grant.holder,
+            revoked_by: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ============= TIMELOCK =============
+
+    pub fn queue_action(
+        ctx: Context<QueueAction>,
+        action: TimelockedAction,
+    ) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        match action.required_role() {
+            Some(role) => require_role(
+                ctx.accounts.role_grant.as_ref(),
+                ctx.accounts.mint.key(),
+                authority,
+                role,
+            )?,
+            None => require!(
+                authority == ctx.accounts.token_info.authority,
+                TokenError::UnauthorizedAccess
+            ),
+        }
+
+        match &action {
+            TimelockedAction::DrainLiquidity {
+                amount,
+                liquidity_pool,
+                treasury,
+            } => {
+                let pool = ctx
+                    .accounts
+                    .liquidity_pool
+                    .as_ref()
+                    .ok_or(TokenError::MissingActionAccount)?;
+                let treasury_account = ctx
+                    .accounts
+                    .treasury
+                    .as_ref()
+                    .ok_or(TokenError::MissingActionAccount)?;
+                require_keys_eq!(pool.key(), *liquidity_pool, TokenError::MissingActionAccount);
+                require_keys_eq!(treasury_account.key(), *treasury, TokenError::MissingActionAccount);
+                let treasury_pool = ctx
+                    .accounts
+                    .treasury_pool
+                    .as_ref()
+                    .ok_or(TokenError::PoolNotRegistered)?;
+                require_keys_eq!(treasury_pool.pool, pool.key(), TokenError::PoolNotRegistered);
+                require_keys_eq!(
+                    treasury_account.key(),
+                    ctx.accounts.token_info.treasury,
+                    TokenError::TreasuryMismatch
+                );
+                require!(*amount <= treasury_pool.epoch_cap, TokenError::EpochOutflowCapExceeded);
+            }
+            TimelockedAction::SetTimelockDelay { kind, delay } => {
+                require!(
+                    (kind.min_delay()..=MAX_TIMELOCK_DELAY).contains(delay),
+                    TokenError::InvalidTimelockDelay
+                );
+            }
+            TimelockedAction::SetTransferLimits {
+                max_tx_amount,
+                max_wallet_balance,
+            } => {
+                validate_transfer_limits(*max_tx_amount, *max_wallet_balance)?;
+            }
+            TimelockedAction::SetVelocityLimits {
+                velocity_limit,
+                whitelisted_velocity_limit,
+            } => {
+                validate_transfer_limits(*velocity_limit, *whitelisted_velocity_limit)?;
+            }
+            _ => {}
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let delay = ctx.accounts.token_info.timelock_delays.get(action.kind());
+        let eta = now.checked_add(delay).ok_or(TokenError::MathOverflow)?;
+
+        let token_info = &mut ctx.accounts.token_info;
+        let queued_action = &mut ctx.accounts.queued_action;
+        queued_action.mint = ctx.accounts.mint.key();
+        queued_action.index = token_info.queued_action_count;
+        queued_action.action = action.clone();
+        queued_action.queued_by = ctx.accounts.authority.key();
+        queued_action.queued_at = now;
+        queued_action.eta = eta;
+        queued_action.bump = ctx.bumps.queued_action;
+
+        token_info.queued_action_count = token_info
+            .queued_action_count
+            .checked_add(1)
+            .ok_or(TokenError::MathOverflow)?;
+
+        emit!(ActionQueued {
+            mint: queued_action.mint,
+            queued_action: queued_action.key(),
+            index: queued_action.index,
+            action,
+            eta,
+            authority: queued_action.queued_by,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless once the action's eta has passed.
+    pub fn execute_queued_action(ctx: Context<ExecuteQueuedAction>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let queued_action = &ctx.accounts.queued_action;
+        require!(now >= queued_action.eta, TokenError::TimelockNotElapsed);
+
+        let action = queued_action.action.clone();
+        let queued_by = queued_action.queued_by;
+        match &action {
+            TimelockedAction::ToggleTrading { enabled } => {
+                set_trading_enabled(&mut ctx.accounts.token_info, *enabled, queued_by)?;
+            }
+            TimelockedAction::MintTokens { amount, destination } => {
+                let destination_account = ctx
+                    .accounts
+                    .destination
+                    .as_ref()
+                    .ok_or(TokenError::MissingActionAccount)?;
+                require_keys_eq!(
+                    destination_account.key(),
+                    *destination,
+                    TokenError::MissingActionAccount
+                );
+                let destination_whitelist = ctx
+                    .accounts
+                    .destination_whitelist
+                    .as_ref()
+                    .ok_or(TokenError::MissingActionAccount)?;
+                let (expected_whitelist, _) = Pubkey::find_program_address(
+                    &[b"whitelist", ctx.accounts.mint.key().as_ref(), destination_account.owner.as_ref()],
+                    &crate::ID,
+                );
+                require_keys_eq!(
+                    destination_whitelist.key(),
+                    expected_whitelist,
+                    TokenError::MissingActionAccount
+                );
+                record_whitelist_receipt(&ctx.accounts.token_info, destination_whitelist, *amount)?;
+                let bucket = ctx
+                    .accounts
+                    .bucket
+                    .as_mut()
+                    .ok_or(TokenError::MissingActionAccount)?;
+                require_keys_eq!(
+                    bucket.destination,
+                    *destination,
+                    TokenError::BucketDestinationMismatch
+                );
+                mint_with_pda(
+                    &mut ctx.accounts.token_info,
+                    ctx.accounts.mint.to_account_info(),
+                    destination_account.to_account_info(),
+                    ctx.accounts.mint_authority.to_account_info(),
+                    ctx.accounts.token_program.to_account_info(),
+                    *amount,
+                )?;
+                record_bucket_mint(bucket, *amount)?;
+            }
+            TimelockedAction::DrainLiquidity {
+                amount,
+                liquidity_pool,
+                treasury,
+            } => {
+                let pool = ctx
+                    .accounts
+                    .liquidity_pool
+                    .as_ref()
+                    .ok_or(TokenError::MissingActionAccount)?;
+                let treasury_account = ctx
+                    .accounts
+                    .treasury
+                    .as_ref()
+                    .ok_or(TokenError::MissingActionAccount)?;
+                require_keys_eq!(pool.key(), *liquidity_pool, TokenError::MissingActionAccount);
+                require_keys_eq!(treasury_account.key(), *treasury, TokenError::MissingActionAccount);
+                require_keys_eq!(
+                    treasury_account.key(),
+                    ctx.accounts.token_info.treasury,
+                    TokenError::TreasuryMismatch
+                );
+                let treasury_pool = ctx
+                    .accounts
+                    .treasury_pool
+                    .as_mut()
+                    .ok_or(TokenError::PoolNotRegistered)?;
+                require_keys_eq!(treasury_pool.pool, pool.key(), TokenError::PoolNotRegistered);
+                record_treasury_outflow(treasury_pool, *amount, Clock::get()?.epoch)?;
+
+                let mint_key = ctx.accounts.mint.key();
+                let seeds = &[
+                    TIMELOCK_AUTHORITY_SEED,
+                    mint_key.as_ref(),
+                    &[ctx.bumps.timelock_authority],
+                ];
+                let signer = &[&seeds[..]];
+                let cpi_accounts = TransferChecked {
+                    from: pool.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: treasury_account.to_account_info(),
+                    authority: ctx.accounts.timelock_authority.to_account_info(),
+                };
+                let cpi_program = ctx.accounts.token_program.to_account_info();
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::transfer_checked(cpi_ctx, *amount, ctx.accounts.token_info.decimals)?;
+
+                emit!(LiquidityDrained {
+                    from: pool.key(),
+                    to: treasury_account.key(),
+                    amount: *amount,
+                    epoch: treasury_pool.epoch,
+                    epoch_outflow: treasury_pool.epoch_outflow,
+                    authority: queued_by,
+                });
+            }
+            TimelockedAction::ProposeAuthority { new_authority } => {
+                set_pending_authority(&mut ctx.accounts.token_info, *new_authority)?;
+            }
+            TimelockedAction::SetTimelockDelay { kind, delay } => {
+                set_timelock_delay(&mut ctx.accounts.token_info, *kind, *delay, queued_by)?;
+            }
+            TimelockedAction::SetTransferLimits {
+                max_tx_amount,
+                max_wallet_balance,
+            } => {
+                set_transfer_limits(
+                    &mut ctx.accounts.token_info,
+                    *max_tx_amount,
+                    *max_wallet_balance,
+                    queued_by,
+                )?;
+            }
+            TimelockedAction::SetVelocityLimits {
+                velocity_limit,
+                whitelisted_velocity_limit,
+            } => {
+                set_velocity_limits(
+                    &mut ctx.accounts.token_info,
+                    *velocity_limit,
+                    *whitelisted_velocity_limit,
+                    queued_by,
+                )?;
+            }
+        }
+
+        emit!(QueuedActionExecuted {
+            mint: ctx.accounts.mint.key(),
+            queued_action: ctx.accounts.queued_action.key(),
+            index: ctx.accounts.queued_action.index,
+            action,
+            executor: ctx.accounts.executor.key(),
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_queued_action(ctx: Context<CancelQueuedAction>) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        require!(
+            authority == ctx.accounts.token_info.authority
+                || authority == ctx.accounts.queued_action.queued_by,
+            TokenError::UnauthorizedAccess
+        );
+
+        let queued_action = &ctx.accounts.queued_action;
+        emit!(QueuedActionCancelled {
+            mint: ctx.accounts.mint.key(),
+            queued_action: queued_action.key(),
+            index: queued_action.index,
+            action: queued_action.action.clone(),
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Delays can be raised immediately; lowering one has to go through the
+    /// queue as `TimelockedAction::SetTimelockDelay`.
+    pub fn increase_timelock_delay(
+        ctx: Context<UpdateTimelockDelay>,
+        kind: TimelockKind,
+        delay: i64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.token_info.authority,
+            TokenError::UnauthorizedAccess
+        );
+        require!(
+            delay >= ctx.accounts.token_info.timelock_delays.get(kind),
+            TokenError::TimelockDecreaseRequiresQueue
+        );
+        require!(delay <= MAX_TIMELOCK_DELAY, TokenError::InvalidTimelockDelay);
+
+        set_timelock_delay(
+            &mut ctx.accounts.token_info,
+            kind,
+            delay,
+            ctx.accounts.authority.key(),
+        )
+    }
+
+    // ============= ADMIN COUNCIL =============
+
+    pub fn create_admin_council(
+        ctx: Context<CreateAdminCouncil>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.token_info.authority,
+            TokenError::UnauthorizedAccess
+        );
+        validate_council(&members, threshold)?;
+
+        let council = &mut ctx.accounts.council;
+        council.mint = ctx.accounts.mint.key();
+        council.members = members.clone();
+        council.threshold = threshold;
+        council.proposal_count = 0;
+        council.bump = ctx.bumps.council;
+        council.authority_bump = ctx.bumps.council_authority;
+
+        emit!(AdminCouncilUpdated {
+            council: council.key(),
+            council_authority: ctx.accounts.council_authority.key(),
+            members,
+            threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Only callable through an executed council proposal, since the council
+    /// authority PDA can only sign from `execute_council_proposal`.
+    pub fn update_admin_council(
+        ctx: Context<UpdateAdminCouncil>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        validate_council(&members, threshold)?;
+
+        let council = &mut ctx.accounts.council;
+        council.members = members.clone();
+        council.threshold = threshold;
+
+        emit!(AdminCouncilUpdated {
+            council: council.key(),
+            council_authority: ctx.accounts.council_authority.key(),
+            members,
+            threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn create_council_proposal(
+        ctx: Context<CreateCouncilProposal>,
+        data: Vec<u8>,
+        accounts: Vec<ProposalAccountMeta>,
+    ) -> Result<()> {
+        let council = &mut ctx.accounts.council;
+        let proposer = ctx.accounts.proposer.key();
+        require!(council.is_member(&proposer), TokenError::NotCouncilMember);
+        require!(
+            data.len() <= MAX_PROPOSAL_DATA_LEN && accounts.len() <= MAX_PROPOSAL_ACCOUNTS,
+            TokenError::ProposalTooLarge
+        );
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.council = council.key();
+        proposal.index = council.proposal_count;
+        proposal.proposer = proposer;
+        proposal.data = data;
+        proposal.accounts = accounts;
+        proposal.approvals = vec![proposer];
+        proposal.executed = false;
+        proposal.created_at = Clock::get()?.unix_timestamp;
+        proposal.bump = ctx.bumps.proposal;
+
+        council.proposal_count = council
+            .proposal_count
+            .checked_add(1)
+            .ok_or(TokenError::MathOverflow)?;
+
+        emit!(CouncilProposalCreated {
+            council: council.key(),
+            proposal: proposal.key(),
+            index: proposal.index,
+            proposer,
+            timestamp: proposal.created_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn approve_council_proposal(ctx: Context<ApproveCouncilProposal>) -> Result<()> {
+        let council = &ctx.accounts.council;
+        let proposal = &mut ctx.accounts.proposal;
+        let member = ctx.accounts.member.key();
+        require!(council.is_member(&member), TokenError::NotCouncilMember);
+        require!(!proposal.executed, TokenError::ProposalAlreadyExecuted);
+        require!(
+            !proposal.approvals.contains(&member),
+            TokenError::ProposalAlreadyApproved
+        );
+
+        council.prune_approvals(&mut proposal.approvals);
+        require!(
+            proposal.approvals.len() < council.members.len(),
+            TokenError::ProposalAlreadyApproved
+        );
+        proposal.approvals.push(member);
+
+        emit!(CouncilProposalApproved {
+            council: council.key(),
+            proposal: proposal.key(),
+            member,
+            approvals: proposal.approvals.len() as u8,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Replays the proposed instruction against this program with the council
+    /// authority PDA as an extra signer. The accounts passed in
+    /// `remaining_accounts` must match the ones recorded in the proposal.
+    pub fn execute_council_proposal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteCouncilProposal<'info>>,
+    ) -> Result<()> {
+        let council = &ctx.accounts.council;
+        let proposal = &mut ctx.accounts.proposal;
+        require!(!proposal.executed, TokenError::ProposalAlreadyExecuted);
+        council.prune_approvals(&mut proposal.approvals);
+        let approvals = proposal.approvals.len();
+        require!(
+            approvals >= council.threshold as usize,
+            TokenError::ProposalThresholdNotMet
+        );
+
+        let remaining = ctx.remaining_accounts;
+        require!(
+            remaining.len() == proposal.accounts.len(),
+            TokenError::ProposalAccountsMismatch
+        );
+        let council_authority = ctx.accounts.council_authority.key();
+        let mut metas = Vec::with_capacity(remaining.len());
+        for (meta, info) in proposal.accounts.iter().zip(remaining.iter()) {
+            require_keys_eq!(meta.pubkey, info.key(), TokenError::ProposalAccountsMismatch);
+            require!(
+                !meta.is_writable || info.is_writable,
+                TokenError::ProposalAccountsMismatch
+            );
+            metas.push(AccountMeta {
+                pubkey: meta.pubkey,
+                is_signer: meta.is_signer || meta.pubkey == council_authority,
+                is_writable: meta.is_writable,
+            });
+        }
+
+        // Persist the executed flag before the CPI so the proposal cannot be
+        // replayed from within the instruction it carries.
+        proposal.executed = true;
+        proposal.exit(&crate::ID)?;
+
+        let instruction = Instruction {
+            program_id: crate::ID,
+            accounts: metas,
+            data: proposal.data.clone(),
+        };
+        let mut account_infos = remaining.to_vec();
+        account_infos.push(ctx.accounts.council_authority.to_account_info());
+        account_infos.push(ctx.accounts.this_program.to_account_info());
+
+        let council_key = council.key();
+        let seeds = &[
+            COUNCIL_AUTHORITY_SEED,
+            council_key.as_ref(),
+            &[council.authority_bump],
+        ];
+        invoke_signed(&instruction, &account_infos, &[&seeds[..]])?;
+
+        emit!(CouncilProposalExecuted {
+            council: council_key,
+            proposal: proposal.key(),
+            index: proposal.index,
+            executor: ctx.accounts.executor.key(),
+            approvals: approvals as u8,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Permanently drops the SPL mint authority. The freeze authority stays
+    /// with the program PDA.
+    pub fn renounce_minting(ctx: Context<RenounceMinting>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.token_info.authority,
+            TokenError::UnauthorizedAccess
+        );
+        require!(
+            !ctx.accounts.token_info.minting_renounced,
+            TokenError::MintingRenounced
+        );
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            MINT_AUTHORITY_SEED,
+            mint_key.as_ref(),
+            &[ctx.accounts.token_info.mint_authority_bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = SetAuthority {
+            account_or_mint: ctx.accounts.mint.to_account_info(),
+            current_authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::set_authority(cpi_ctx, AuthorityType::MintTokens, None)?;
+
+        let token_info = &mut ctx.accounts.token_info;
+        token_info.minting_renounced = true;
+
+        emit!(MintingRenounced {
+            mint: mint_key,
+            authority: ctx.accounts.authority.key(),
+            final_supply: token_info.total_supply,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // ============= METADATA =============
+
+    /// Updates name, symbol and uri in both `TokenInfo` and the Metaplex
+    /// metadata account so wallets and the program never disagree.
+    pub fn update_token_metadata(
+        ctx: Context<UpdateTokenMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.token_info.authority,
+            TokenError::UnauthorizedAccess
+        );
+        validate_metadata(&name, &symbol, &uri)?;
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            MINT_AUTHORITY_SEED,
+            mint_key.as_ref(),
+            &[ctx.accounts.token_info.mint_authority_bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = UpdateMetadataAccountsV2 {
+            metadata: ctx.accounts.metadata.to_account_info(),
+            update_authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_metadata_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        metadata::update_metadata_accounts_v2(
+            cpi_ctx,
+            None,
+            Some(token_metadata_data(&name, &symbol, &uri)),
+            None,
+            None,
+        )?;
+
+        let token_info = &mut ctx.accounts.token_info;
+        token_info.name = name;
+        token_info.symbol = symbol;
+        token_info.uri = uri;
+
+        emit!(TokenMetadataUpdated {
+            mint: mint_key,
+            name: token_info.name.clone(),
+            symbol: token_info.symbol.clone(),
+            uri: token_info.uri.clone(),
+            authority: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+
+    // ============= MIGRATIONS =============
+
+    /// Moves the SPL mint (and freeze) authority of a mint created before the
+    /// program-owned mint authority was introduced over to the PDA, and
+    /// rewrites its `TokenInfo` from the original layout into the current one.
+    /// Token accounts the old freeze authority left frozen go in
+    /// `remaining_accounts`, so `thaw_account` can release them too.
+    pub fn migrate_mint_authority<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateMintAuthority<'info>>,
+        max_supply: u64,
+    ) -> Result<()> {
+        let legacy = load_legacy_token_info(&ctx.accounts.token_info)?;
+        require!(
+            ctx.accounts.authority.key() == legacy.authority,
+            TokenError::UnauthorizedAccess
+        );
+        // Older tokens had no cap; it must still cover what is already minted
+        require!(
+            max_supply > 0 && max_supply >= legacy.total_supply,
+            TokenError::InvalidMaxSupply
+        );
+
+        let mint = &ctx.accounts.mint;
+        let frozen_accounts = count_frozen_accounts(
+            ctx.remaining_accounts,
+            &mint.key(),
+            ctx.accounts.token_program.key,
+        )?;
+        let old_mint_authority = ctx.accounts.current_mint_authority.key();
+        let new_mint_authority = ctx.accounts.mint_authority.key();
+        require!(
+            mint.mint_authority == COption::Some(old_mint_authority),
+            TokenError::InvalidMintAuthority
+        );
+
+        let cpi_accounts = SetAuthority {
+            account_or_mint: mint.to_account_info(),
+            current_authority: ctx.accounts.current_mint_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token_interface::set_authority(cpi_ctx, AuthorityType::MintTokens, Some(new_mint_authority))?;
+
+        // A freeze authority that was already dropped cannot be re-assigned.
+        let freeze_authority_migrated = mint.freeze_authority == COption::Some(old_mint_authority);
+        if freeze_authority_migrated {
+            let cpi_accounts = SetAuthority {
+                account_or_mint: mint.to_account_info(),
+                current_authority: ctx.accounts.current_mint_authority.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+            token_interface::set_authority(cpi_ctx, AuthorityType::FreezeAccount, Some(new_mint_authority))?;
+        }
+
+        // Older tokens stored the requested decimals while the mint was always
+        // created with 9; the mint is the source of truth.
+        let mut token_info = TokenInfo::default();
+        init_token_info(
+            &mut token_info,
+            legacy.authority,
+            mint.key(),
+            mint.decimals,
+            max_supply,
+            ctx.bumps.mint_authority,
+        )?;
+        token_info.name = legacy.name;
+        token_info.symbol = legacy.symbol;
+        token_info.uri = legacy.uri;
+        token_info.total_supply = legacy.total_supply;
+        token_info.is_initialized = legacy.is_initialized;
+        token_info.whitelist_enabled = legacy.whitelist_enabled;
+        token_info.trading_enabled = legacy.trading_enabled;
+        token_info.blacklist_enabled = legacy.blacklist_enabled;
+        token_info.frozen_accounts = frozen_accounts;
+
+        let account = ctx.accounts.token_info.to_account_info();
+        grow_account(
+            &account,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + TokenInfo::INIT_SPACE,
+        )?;
+        let mut data = account.try_borrow_mut_data()?;
+        data.fill(0);
+        token_info.try_serialize(&mut &mut data[..])?;
+
+        emit!(MintAuthorityMigrated {
+            mint: mint.key(),
+            old_mint_authority,
+            new_mint_authority,
+            freeze_authority_migrated,
+        });
+
+        Ok(())
+    }
+}
+
+// ============= ACCOUNT STRUCTS =============
 
 #[derive(Accounts)]
+#[instruction(name: String, symbol: String, uri: String, decimals: u8)]
 pub struct InitializeToken<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -399,10 +2427,19 @@ pub struct InitializeToken<'info> {
     #[account(
         init,
         payer = authority,
-        mint::decimals = 9,
-        mint::authority = authority,
+        mint::decimals = decimals,
+        mint::authority = mint_authority,
+        mint::freeze_authority = mint_authority,
+        mint::token_program = token_program,
     )]
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    /// CHECK: PDA that holds the mint and freeze authority
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
     
     #[account(
         init,
@@ -413,67 +2450,134 @@ pub struct InitializeToken<'info> {
     )]
     pub token_info: Account<'info, TokenInfo>,
     
+    /// CHECK: Metaplex metadata account, created by the metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+    )]
+    pub metadata: UncheckedAccount<'info>,
+    
     pub token_program: Program<'info, Token>,
+    pub token_metadata_program: Program<'info, Metadata>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct MintTokens<'info> {
+#[instruction(name: String, symbol: String, uri: String, decimals: u8)]
+pub struct InitializeToken2022<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     
+    /// Created by the handler; `mint::` constraints cannot add the transfer
+    /// fee extension
     #[account(mut)]
-    pub mint: Account<'info, Mint>,
+    pub mint: Signer<'info>,
     
+    /// CHECK: PDA that holds the mint, freeze and transfer hook authority
     #[account(
-        mut,
+        seeds = [MINT_AUTHORITY_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TokenInfo::INIT_SPACE,
         seeds = [b"token_info", mint.key().as_ref()],
         bump,
     )]
     pub token_info: Account<'info, TokenInfo>,
     
+    /// CHECK: Extra account list read by Token-2022, written by this instruction
     #[account(
-        mut,
-        associated_token::mint = mint,
-        associated_token::authority = authority,
+        init,
+        payer = authority,
+        space = ExtraAccountMetaList::size_of(transfer_hook_extra_account_metas()?.len())?,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()],
+        bump,
     )]
-    pub destination: Account<'info, TokenAccount>,
+    pub extra_account_meta_list: UncheckedAccount<'info>,
     
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Metaplex metadata account, created by the metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+    )]
+    pub metadata: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token2022>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct BurnTokens<'info> {
+pub struct MintTokens<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     
     #[account(mut)]
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         mut,
         seeds = [b"token_info", mint.key().as_ref()],
         bump,
+        constraint = token_info.decimals == mint.decimals @ TokenError::DecimalsMismatch,
     )]
     pub token_info: Account<'info, TokenInfo>,
     
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Minter as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    /// CHECK: PDA that holds the mint authority
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, mint.key().as_ref()],
+        bump = token_info.mint_authority_bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    
     #[account(
         mut,
-        associated_token::mint = mint,
-        associated_token::authority = authority,
+        seeds = [BUCKET_SEED, mint.key().as_ref(), &[bucket.category as u8]],
+        bump = bucket.bump,
     )]
-    pub from: Account<'info, TokenAccount>,
+    pub bucket: Account<'info, AllocationBucket>,
     
-    pub token_program: Program<'info, Token>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+        address = bucket.destination @ TokenError::BucketDestinationMismatch,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: Destination owner's whitelist PDA, possibly never created
+    #[account(
+        mut,
+        seeds = [b"whitelist", mint.key().as_ref(), destination.owner.as_ref()],
+        bump,
+    )]
+    pub destination_whitelist: UncheckedAccount<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-pub struct DrainLiquidity<'info> {
+pub struct CreateLiquidityLock<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         seeds = [b"token_info", mint.key().as_ref()],
@@ -482,28 +2586,45 @@ pub struct DrainLiquidity<'info> {
     pub token_info: Account<'info, TokenInfo>,
     
     #[account(
-        mut,
-        token::mint = mint,
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Treasurer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
     )]
-    pub liquidity_pool: Account<'info, TokenAccount>,
+    pub role_grant: Account<'info, RoleGrant>,
     
     #[account(
-        mut,
-        associated_token::mint = mint,
-        associated_token::authority = authority,
+        mint::token_program = lp_token_program,
     )]
-    pub treasury: Account<'info, TokenAccount>,
+    pub lp_mint: InterfaceAccount<'info, Mint>,
     
-    pub token_program: Program<'info, Token>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + LiquidityLock::INIT_SPACE,
+        seeds = [LIQUIDITY_LOCK_SEED, mint.key().as_ref(), lp_mint.key().as_ref()],
+        bump,
+    )]
+    pub liquidity_lock: Account<'info, LiquidityLock>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [LIQUIDITY_LOCK_VAULT_SEED, liquidity_lock.key().as_ref()],
+        bump,
+        token::mint = lp_mint,
+        token::authority = liquidity_lock,
+        token::token_program = lp_token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    pub lp_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(wallet: Pubkey)]
-pub struct ManageBlacklist<'info> {
-    #[account(mut)]
+pub struct DepositLiquidity<'info> {
     pub authority: Signer<'info>,
     
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         seeds = [b"token_info", mint.key().as_ref()],
@@ -512,24 +2633,41 @@ pub struct ManageBlacklist<'info> {
     pub token_info: Account<'info, TokenInfo>,
     
     #[account(
-        init_if_needed,
-        payer = authority,
-        space = 8 + BlacklistEntry::INIT_SPACE,
-        seeds = [b"blacklist", mint.key().as_ref(), wallet.as_ref()],
-        bump,
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Treasurer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
     )]
-    pub blacklist: Account<'info, BlacklistEntry>,
+    pub role_grant: Account<'info, RoleGrant>,
     
-    pub system_program: Program<'info, System>,
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [LIQUIDITY_LOCK_SEED, mint.key().as_ref(), lp_mint.key().as_ref()],
+        bump = liquidity_lock.bump,
+    )]
+    pub liquidity_lock: Account<'info, LiquidityLock>,
+    
+    #[account(
+        mut,
+        address = liquidity_lock.vault,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::token_program = lp_token_program,
+    )]
+    pub source: InterfaceAccount<'info, TokenAccount>,
+    
+    pub lp_token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-#[instruction(wallet: Pubkey)]
-pub struct RemoveBlacklist<'info> {
-    #[account(mut)]
+pub struct ExtendLiquidityLock<'info> {
     pub authority: Signer<'info>,
     
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         seeds = [b"token_info", mint.key().as_ref()],
@@ -537,62 +2675,117 @@ pub struct RemoveBlacklist<'info> {
     )]
     pub token_info: Account<'info, TokenInfo>,
     
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Treasurer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
     #[account(
         mut,
-        seeds = [b"blacklist", mint.key().as_ref(), wallet.as_ref()],
-        bump,
+        seeds = [LIQUIDITY_LOCK_SEED, mint.key().as_ref(), liquidity_lock.lp_mint.as_ref()],
+        bump = liquidity_lock.bump,
     )]
-    pub blacklist: Account<'info, BlacklistEntry>,
+    pub liquidity_lock: Account<'info, LiquidityLock>,
 }
 
 #[derive(Accounts)]
-pub struct ToggleBlacklistSystem<'info> {
-    #[account(mut)]
+pub struct ReleaseLiquidity<'info> {
     pub authority: Signer<'info>,
     
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     
     #[account(
-        mut,
         seeds = [b"token_info", mint.key().as_ref()],
         bump,
     )]
     pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Treasurer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [LIQUIDITY_LOCK_SEED, mint.key().as_ref(), lp_mint.key().as_ref()],
+        bump = liquidity_lock.bump,
+    )]
+    pub liquidity_lock: Account<'info, LiquidityLock>,
+    
+    #[account(
+        mut,
+        address = liquidity_lock.vault,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::token_program = lp_token_program,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    
+    pub lp_token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-#[instruction(wallet: Pubkey)]
-pub struct ManageWhitelist<'info> {
+pub struct ViewLiquidityLock<'info> {
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [LIQUIDITY_LOCK_SEED, mint.key().as_ref(), liquidity_lock.lp_mint.as_ref()],
+        bump = liquidity_lock.bump,
+    )]
+    pub liquidity_lock: Account<'info, LiquidityLock>,
+    
+    #[account(
+        address = liquidity_lock.vault,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(category: BucketCategory)]
+pub struct CreateAllocationBucket<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     
     #[account(
+        mut,
         seeds = [b"token_info", mint.key().as_ref()],
         bump,
     )]
     pub token_info: Account<'info, TokenInfo>,
     
     #[account(
-        init_if_needed,
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Minter as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    #[account(
+        init,
         payer = authority,
-        space = 8 + WhitelistEntry::INIT_SPACE,
-        seeds = [b"whitelist", mint.key().as_ref(), wallet.as_ref()],
+        space = 8 + AllocationBucket::INIT_SPACE,
+        seeds = [BUCKET_SEED, mint.key().as_ref(), &[category as u8]],
         bump,
     )]
-    pub whitelist: Account<'info, WhitelistEntry>,
+    pub bucket: Account<'info, AllocationBucket>,
     
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(wallet: Pubkey)]
-pub struct RemoveWhitelist<'info> {
-    #[account(mut)]
+pub struct ReleaseAllocationBucket<'info> {
     pub authority: Signer<'info>,
     
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         seeds = [b"token_info", mint.key().as_ref()],
@@ -600,35 +2793,63 @@ pub struct RemoveWhitelist<'info> {
     )]
     pub token_info: Account<'info, TokenInfo>,
     
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Minter as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
     #[account(
         mut,
-        seeds = [b"whitelist", mint.key().as_ref(), wallet.as_ref()],
-        bump,
+        seeds = [BUCKET_SEED, mint.key().as_ref(), &[bucket.category as u8]],
+        bump = bucket.bump,
     )]
-    pub whitelist: Account<'info, WhitelistEntry>,
+    pub bucket: Account<'info, AllocationBucket>,
 }
 
 #[derive(Accounts)]
-pub struct ToggleWhitelistMode<'info> {
+pub struct ViewAllocationBuckets<'info> {
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct BurnTokens<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     
-    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         mut,
         seeds = [b"token_info", mint.key().as_ref()],
         bump,
+        constraint = token_info.decimals == mint.decimals @ TokenError::DecimalsMismatch,
     )]
     pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Burner as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program,
+    )]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-pub struct ToggleTrading<'info> {
-    #[account(mut)]
+pub struct SetTreasury<'info> {
     pub authority: Signer<'info>,
     
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         mut,
@@ -636,13 +2857,25 @@ pub struct ToggleTrading<'info> {
         bump,
     )]
     pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Treasurer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    #[account(
+        token::mint = mint,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
 }
 
 #[derive(Accounts)]
-pub struct ControlledTransfer<'info> {
+pub struct RegisterTreasuryPool<'info> {
+    #[account(mut)]
     pub authority: Signer<'info>,
     
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         seeds = [b"token_info", mint.key().as_ref()],
@@ -650,161 +2883,3087 @@ pub struct ControlledTransfer<'info> {
     )]
     pub token_info: Account<'info, TokenInfo>,
     
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Treasurer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    /// CHECK: PDA that takes ownership of the pool
+    #[account(
+        seeds = [TIMELOCK_AUTHORITY_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub timelock_authority: UncheckedAccount<'info>,
+    
     #[account(
         mut,
         token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub liquidity_pool: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TreasuryPool::INIT_SPACE,
+        seeds = [TREASURY_POOL_SEED, mint.key().as_ref(), liquidity_pool.key().as_ref()],
+        bump,
+    )]
+    pub treasury_pool: Account<'info, TreasuryPool>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DeregisterTreasuryPool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Treasurer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    /// CHECK: PDA that owns the pool while it is registered
+    #[account(
+        seeds = [TIMELOCK_AUTHORITY_SEED, mint.key().as_ref()],
+        bump,
     )]
-    pub from: Account<'info, TokenAccount>,
+    pub timelock_authority: UncheckedAccount<'info>,
     
     #[account(
         mut,
-        token::mint = mint,
+        address = treasury_pool.pool @ TokenError::PoolNotRegistered,
+        token::token_program = token_program,
+    )]
+    pub liquidity_pool: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [TREASURY_POOL_SEED, mint.key().as_ref(), treasury_pool.pool.as_ref()],
+        bump = treasury_pool.bump,
     )]
-    pub to: Account<'info, TokenAccount>,
+    pub treasury_pool: Account<'info, TreasuryPool>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct ManageBlacklist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
     
-    /// CHECK: Optional blacklist check for sender
-    pub sender_blacklist: Option<Account<'info, BlacklistEntry>>,
+    pub mint: InterfaceAccount<'info, Mint>,
     
-    /// CHECK: Optional blacklist check for recipient
-    pub recipient_blacklist: Option<Account<'info, BlacklistEntry>>,
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
     
-    /// CHECK: Optional whitelist check for sender
-    pub sender_whitelist: Option<Account<'info, WhitelistEntry>>,
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::ComplianceOfficer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
     
-    /// CHECK: Optional whitelist check for recipient
-    pub recipient_whitelist: Option<Account<'info, WhitelistEntry>>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + BlacklistEntry::INIT_SPACE,
+        seeds = [b"blacklist", mint.key().as_ref(), wallet.as_ref()],
+        bump,
+    )]
+    pub blacklist: Account<'info, BlacklistEntry>,
     
-    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FileAppeal<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        mut,
+        seeds = [b"blacklist", mint.key().as_ref(), wallet.key().as_ref()],
+        bump,
+    )]
+    pub blacklist: Account<'info, BlacklistEntry>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct ResolveAppeal<'info> {
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::ComplianceOfficer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    #[account(
+        mut,
+        seeds = [b"blacklist", mint.key().as_ref(), wallet.as_ref()],
+        bump,
+    )]
+    pub blacklist: Account<'info, BlacklistEntry>,
+}
+
+#[derive(Accounts)]
+pub struct SetAppealWindow<'info> {
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::ComplianceOfficer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct RemoveBlacklist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::ComplianceOfficer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    #[account(
+        mut,
+        seeds = [b"blacklist", mint.key().as_ref(), wallet.as_ref()],
+        bump,
+    )]
+    pub blacklist: Account<'info, BlacklistEntry>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct CloseBlacklistEntry<'info> {
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::ComplianceOfficer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    #[account(
+        mut,
+        close = rent_receiver,
+        seeds = [b"blacklist", mint.key().as_ref(), wallet.as_ref()],
+        bump,
+    )]
+    pub blacklist: Account<'info, BlacklistEntry>,
+    
+    /// CHECK: Any account chosen to receive the entry's rent
+    #[account(mut)]
+    pub rent_receiver: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetListRoot<'info> {
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::ComplianceOfficer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+}
+
+#[derive(Accounts)]
+pub struct ToggleBlacklistSystem<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::ComplianceOfficer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct ManageWhitelist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::ComplianceOfficer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + WhitelistEntry::INIT_SPACE,
+        seeds = [b"whitelist", mint.key().as_ref(), wallet.as_ref()],
+        bump,
+    )]
+    pub whitelist: Account<'info, WhitelistEntry>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct RemoveWhitelist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::ComplianceOfficer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    #[account(
+        mut,
+        seeds = [b"whitelist", mint.key().as_ref(), wallet.as_ref()],
+        bump,
+    )]
+    pub whitelist: Account<'info, WhitelistEntry>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct CloseWhitelistEntry<'info> {
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::ComplianceOfficer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    #[account(
+        mut,
+        close = rent_receiver,
+        seeds = [b"whitelist", mint.key().as_ref(), wallet.as_ref()],
+        bump,
+    )]
+    pub whitelist: Account<'info, WhitelistEntry>,
+    
+    /// CHECK: Any account chosen to receive the entry's rent
+    #[account(mut)]
+    pub rent_receiver: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BatchManageCompliance<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::ComplianceOfficer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct ManageAllocation<'info> {
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::ComplianceOfficer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    #[account(
+        mut,
+        seeds = [b"whitelist", mint.key().as_ref(), wallet.as_ref()],
+        bump,
+    )]
+    pub whitelist: Account<'info, WhitelistEntry>,
+}
+
+#[derive(Accounts)]
+pub struct ToggleWhitelistMode<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::ComplianceOfficer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+}
+
+#[derive(Accounts)]
+pub struct ToggleTrading<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Pauser as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeTokenAccount<'info> {
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Pauser as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    /// CHECK: PDA that holds the freeze authority
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, mint.key().as_ref()],
+        bump = token_info.mint_authority_bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub account: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTransferLimits<'info> {
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Treasurer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+}
+
+#[derive(Accounts)]
+pub struct AddLimitExemption<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Treasurer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    #[account(
+        token::mint = mint,
+    )]
+    pub account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TransferLimitExemption::INIT_SPACE,
+        seeds = [LIMIT_EXEMPTION_SEED, mint.key().as_ref(), account.key().as_ref()],
+        bump,
+    )]
+    pub exemption: Account<'info, TransferLimitExemption>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveLimitExemption<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Treasurer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [LIMIT_EXEMPTION_SEED, mint.key().as_ref(), exemption.account.as_ref()],
+        bump = exemption.bump,
+    )]
+    pub exemption: Account<'info, TransferLimitExemption>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureTransferFee<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Treasurer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    /// CHECK: PDA that holds the transfer fee config authority
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, mint.key().as_ref()],
+        bump = token_info.mint_authority_bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub fee_collector: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct AddFeeExemption<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Treasurer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    #[account(
+        token::mint = mint,
+    )]
+    pub account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TransferFeeExemption::INIT_SPACE,
+        seeds = [FEE_EXEMPTION_SEED, mint.key().as_ref(), account.key().as_ref()],
+        bump,
+    )]
+    pub exemption: Account<'info, TransferFeeExemption>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFeeExemption<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Treasurer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [FEE_EXEMPTION_SEED, mint.key().as_ref(), exemption.account.as_ref()],
+        bump = exemption.bump,
+    )]
+    pub exemption: Account<'info, TransferFeeExemption>,
+}
+
+#[derive(Accounts)]
+pub struct HarvestWithheld<'info> {
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawWithheld<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::Treasurer as u8], authority.key().as_ref()],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    /// CHECK: PDA that holds the withdraw withheld authority
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, mint.key().as_ref()],
+        bump = token_info.mint_authority_bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        address = token_info.fee_collector @ TokenError::FeeCollectorMismatch,
+    )]
+    pub fee_collector: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct ControlledTransfer<'info> {
+    /// Pays for the sender's velocity tracker the first time a limit applies
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+        constraint = token_info.decimals == mint.decimals @ TokenError::DecimalsMismatch,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        mut,
+        token::mint = mint,
+    )]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = mint,
+    )]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: Sender blacklist PDA, possibly never created
+    #[account(
+        seeds = [b"blacklist", mint.key().as_ref(), from.owner.as_ref()],
+        bump,
+    )]
+    pub sender_blacklist: UncheckedAccount<'info>,
+    
+    /// CHECK: Recipient blacklist PDA, possibly never created
+    #[account(
+        seeds = [b"blacklist", mint.key().as_ref(), to.owner.as_ref()],
+        bump,
+    )]
+    pub recipient_blacklist: UncheckedAccount<'info>,
+    
+    /// CHECK: Sender whitelist PDA, possibly never created
+    #[account(
+        seeds = [b"whitelist", mint.key().as_ref(), from.owner.as_ref()],
+        bump,
+    )]
+    pub sender_whitelist: UncheckedAccount<'info>,
+    
+    /// CHECK: Recipient whitelist PDA, possibly never created
+    #[account(
+        mut,
+        seeds = [b"whitelist", mint.key().as_ref(), to.owner.as_ref()],
+        bump,
+    )]
+    pub recipient_whitelist: UncheckedAccount<'info>,
+    
+    /// CHECK: Limit exemption PDA of `from`, possibly never created
+    #[account(
+        seeds = [LIMIT_EXEMPTION_SEED, mint.key().as_ref(), from.key().as_ref()],
+        bump,
+    )]
+    pub sender_limit_exemption: UncheckedAccount<'info>,
+    
+    /// CHECK: Limit exemption PDA of `to`, possibly never created
+    #[account(
+        seeds = [LIMIT_EXEMPTION_SEED, mint.key().as_ref(), to.key().as_ref()],
+        bump,
+    )]
+    pub recipient_limit_exemption: UncheckedAccount<'info>,
+    
+    /// CHECK: Fee exemption PDA of `from`, possibly never created
+    #[account(
+        seeds = [FEE_EXEMPTION_SEED, mint.key().as_ref(), from.key().as_ref()],
+        bump,
+    )]
+    pub sender_fee_exemption: UncheckedAccount<'info>,
+    
+    /// CHECK: Fee exemption PDA of `to`, possibly never created
+    #[account(
+        seeds = [FEE_EXEMPTION_SEED, mint.key().as_ref(), to.key().as_ref()],
+        bump,
+    )]
+    pub recipient_fee_exemption: UncheckedAccount<'info>,
+    
+    /// Required when this transfer is charged a fee
+    #[account(
+        mut,
+        address = token_info.fee_collector @ TokenError::FeeCollectorMismatch,
+    )]
+    pub fee_collector: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// CHECK: Sender's velocity tracker PDA, created on first use
+    #[account(
+        mut,
+        seeds = [VELOCITY_TRACKER_SEED, mint.key().as_ref(), from.owner.as_ref()],
+        bump,
+    )]
+    pub sender_velocity: UncheckedAccount<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Account order is fixed by the transfer hook interface: the first five come
+/// from Token-2022, the rest are resolved from `extra_account_meta_list`.
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+    
+    /// CHECK: Source owner or delegate, already verified by Token-2022
+    pub owner: UncheckedAccount<'info>,
+    
+    /// CHECK: Extra account list for this mint
+    #[account(
+        seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    /// CHECK: Sender blacklist PDA, possibly never created
+    #[account(
+        seeds = [b"blacklist", mint.key().as_ref(), source_token.owner.as_ref()],
+        bump,
+    )]
+    pub sender_blacklist: UncheckedAccount<'info>,
+    
+    /// CHECK: Recipient blacklist PDA, possibly never created
+    #[account(
+        seeds = [b"blacklist", mint.key().as_ref(), destination_token.owner.as_ref()],
+        bump,
+    )]
+    pub recipient_blacklist: UncheckedAccount<'info>,
+    
+    /// CHECK: Sender whitelist PDA, possibly never created
+    #[account(
+        seeds = [b"whitelist", mint.key().as_ref(), source_token.owner.as_ref()],
+        bump,
+    )]
+    pub sender_whitelist: UncheckedAccount<'info>,
+    
+    /// CHECK: Recipient whitelist PDA, possibly never created
+    #[account(
+        mut,
+        seeds = [b"whitelist", mint.key().as_ref(), destination_token.owner.as_ref()],
+        bump,
+    )]
+    pub recipient_whitelist: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferAuthority<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub new_authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+}
+
+#[derive(Accounts)]
+#[instruction(role: Role, holder: Pubkey)]
+pub struct GrantRole<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::RoleAdmin as u8], admin.key().as_ref()],
+        bump = admin_grant.bump,
+    )]
+    pub admin_grant: Option<Account<'info, RoleGrant>>,
+    
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + RoleGrant::INIT_SPACE,
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[role as u8], holder.as_ref()],
+        bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeRole<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        seeds = [ROLE_SEED, mint.key().as_ref(), &[Role::RoleAdmin as u8], admin.key().as_ref()],
+        bump = admin_grant.bump,
+    )]
+    pub admin_grant: Option<Account<'info, RoleGrant>>,
+    
+    #[account(
+        mut,
+        close = admin,
+        seeds = [
+            ROLE_SEED,
+            mint.key().as_ref(),
+            &[role_grant.role as u8],
+            role_grant.holder.as_ref(),
+        ],
+        bump = role_grant.bump,
+    )]
+    pub role_grant: Account<'info, RoleGrant>,
+}
+
+#[derive(Accounts)]
+pub struct QueueAction<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + QueuedAction::INIT_SPACE,
+        seeds = [
+            QUEUED_ACTION_SEED,
+            mint.key().as_ref(),
+            token_info.queued_action_count.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub queued_action: Account<'info, QueuedAction>,
+    
+    /// Grant of the role the queued action requires, if any
+    pub role_grant: Option<Account<'info, RoleGrant>>,
+    
+    #[account(
+        token::mint = mint,
+    )]
+    pub liquidity_pool: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        token::mint = mint,
+    )]
+    pub treasury: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Registration of `liquidity_pool`, required for drains
+    #[account(
+        seeds = [TREASURY_POOL_SEED, mint.key().as_ref(), treasury_pool.pool.as_ref()],
+        bump = treasury_pool.bump,
+    )]
+    pub treasury_pool: Option<Account<'info, TreasuryPool>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteQueuedAction<'info> {
+    pub executor: Signer<'info>,
+    
+    /// CHECK: Receives the rent of the closed queue entry
+    #[account(
+        mut,
+        address = queued_action.queued_by,
+    )]
+    pub queued_by: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+        constraint = token_info.decimals == mint.decimals @ TokenError::DecimalsMismatch,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        mut,
+        close = queued_by,
+        seeds = [
+            QUEUED_ACTION_SEED,
+            mint.key().as_ref(),
+            queued_action.index.to_le_bytes().as_ref(),
+        ],
+        bump = queued_action.bump,
+    )]
+    pub queued_action: Account<'info, QueuedAction>,
+    
+    /// CHECK: PDA that holds the mint authority
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, mint.key().as_ref()],
+        bump = token_info.mint_authority_bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    /// CHECK: PDA that owns every registered liquidity pool
+    #[account(
+        seeds = [TIMELOCK_AUTHORITY_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub timelock_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        token::mint = mint,
+    )]
+    pub destination: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// CHECK: Whitelist PDA of the destination owner, verified in the handler
+    #[account(mut)]
+    pub destination_whitelist: Option<UncheckedAccount<'info>>,
+    
+    #[account(
+        mut,
+        seeds = [BUCKET_SEED, mint.key().as_ref(), &[bucket.category as u8]],
+        bump = bucket.bump,
+    )]
+    pub bucket: Option<Account<'info, AllocationBucket>>,
+    
+    #[account(
+        mut,
+        token::mint = mint,
+    )]
+    pub liquidity_pool: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        token::mint = mint,
+    )]
+    pub treasury: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Registration of `liquidity_pool`; tracks the epoch outflow
+    #[account(
+        mut,
+        seeds = [TREASURY_POOL_SEED, mint.key().as_ref(), treasury_pool.pool.as_ref()],
+        bump = treasury_pool.bump,
+    )]
+    pub treasury_pool: Option<Account<'info, TreasuryPool>>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelQueuedAction<'info> {
+    pub authority: Signer<'info>,
+    
+    /// CHECK: Receives the rent of the closed queue entry
+    #[account(
+        mut,
+        address = queued_action.queued_by,
+    )]
+    pub queued_by: UncheckedAccount<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        mut,
+        close = queued_by,
+        seeds = [
+            QUEUED_ACTION_SEED,
+            mint.key().as_ref(),
+            queued_action.index.to_le_bytes().as_ref(),
+        ],
+        bump = queued_action.bump,
+    )]
+    pub queued_action: Account<'info, QueuedAction>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTimelockDelay<'info> {
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+}
+
+#[derive(Accounts)]
+pub struct CreateAdminCouncil<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminCouncil::INIT_SPACE,
+        seeds = [ADMIN_COUNCIL_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub council: Account<'info, AdminCouncil>,
+    
+    /// CHECK: System-owned PDA that signs for the council
+    #[account(
+        seeds = [COUNCIL_AUTHORITY_SEED, council.key().as_ref()],
+        bump,
+    )]
+    pub council_authority: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAdminCouncil<'info> {
+    #[account(
+        seeds = [COUNCIL_AUTHORITY_SEED, council.key().as_ref()],
+        bump = council.authority_bump,
+    )]
+    pub council_authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_COUNCIL_SEED, council.mint.as_ref()],
+        bump = council.bump,
+    )]
+    pub council: Account<'info, AdminCouncil>,
+}
+
+#[derive(Accounts)]
+pub struct CreateCouncilProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_COUNCIL_SEED, council.mint.as_ref()],
+        bump = council.bump,
+    )]
+    pub council: Account<'info, AdminCouncil>,
+    
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + CouncilProposal::INIT_SPACE,
+        seeds = [
+            COUNCIL_PROPOSAL_SEED,
+            council.key().as_ref(),
+            council.proposal_count.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveCouncilProposal<'info> {
+    pub member: Signer<'info>,
+    
+    #[account(
+        seeds = [ADMIN_COUNCIL_SEED, council.mint.as_ref()],
+        bump = council.bump,
+    )]
+    pub council: Account<'info, AdminCouncil>,
+    
+    #[account(
+        mut,
+        seeds = [
+            COUNCIL_PROPOSAL_SEED,
+            council.key().as_ref(),
+            proposal.index.to_le_bytes().as_ref(),
+        ],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteCouncilProposal<'info> {
+    pub executor: Signer<'info>,
+    
+    // Not `mut`: the proposed instruction may itself rewrite the council.
+    #[account(
+        seeds = [ADMIN_COUNCIL_SEED, council.mint.as_ref()],
+        bump = council.bump,
+    )]
+    pub council: Account<'info, AdminCouncil>,
+    
+    /// CHECK: System-owned PDA that signs for the council
+    #[account(
+        mut,
+        seeds = [COUNCIL_AUTHORITY_SEED, council.key().as_ref()],
+        bump = council.authority_bump,
+    )]
+    pub council_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [
+            COUNCIL_PROPOSAL_SEED,
+            council.key().as_ref(),
+            proposal.index.to_le_bytes().as_ref(),
+        ],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+    
+    pub this_program: Program<'info, program::DollarToken>,
+}
+
+#[derive(Accounts)]
+pub struct RenounceMinting<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    /// CHECK: PDA that holds the mint authority
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, mint.key().as_ref()],
+        bump = token_info.mint_authority_bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTokenMetadata<'info> {
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: Account<'info, TokenInfo>,
+    
+    /// CHECK: PDA that is the metadata update authority
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, mint.key().as_ref()],
+        bump = token_info.mint_authority_bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    /// CHECK: Metaplex metadata account, validated by the metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+    )]
+    pub metadata: UncheckedAccount<'info>,
+    
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateMintAuthority<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub current_mint_authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    /// CHECK: read in the original `TokenInfo` layout and rewritten by the handler
+    #[account(
+        mut,
+        seeds = [b"token_info", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_info: UncheckedAccount<'info>,
+    
+    /// CHECK: PDA that becomes the mint and freeze authority
+    #[account(
+        seeds = [MINT_AUTHORITY_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+// ============= DATA STRUCTS =============
+
+#[account]
+#[derive(InitSpace, Default)]
+pub struct TokenInfo {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    #[max_len(MAX_NAME_LEN)]
+    pub name: String,
+    #[max_len(MAX_SYMBOL_LEN)]
+    pub symbol: String,
+    #[max_len(MAX_URI_LEN)]
+    pub uri: String,
+    pub decimals: u8,
+    pub total_supply: u64,
+    pub is_initialized: bool,
+    pub whitelist_enabled: bool,
+    pub trading_enabled: bool,
+    pub blacklist_enabled: bool,
+    pub pending_authority: Option<Pubkey>,
+    pub mint_authority_bump: u8,
+    pub max_supply: u64,
+    pub minting_renounced: bool,
+    pub timelock_delays: TimelockDelays,
+    pub queued_action_count: u64,
+    /// Seconds the compliance team has to resolve a blacklist appeal
+    pub appeal_window: i64,
+    /// Merkle whitelist root; all zeroes when unused
+    pub whitelist_root: [u8; 32],
+    pub whitelist_root_version: u32,
+    /// Merkle root over the sorted blacklist; all zeroes when unused
+    pub blacklist_root: [u8; 32],
+    pub blacklist_root_version: u32,
+    /// Sum of all allocation bucket caps
+    pub bucket_caps_total: u64,
+    /// Token account all drains pay into; default until `set_treasury`
+    pub treasury: Pubkey,
+    /// Largest amount one `controlled_transfer` may move
+    pub max_tx_amount: Option<u64>,
+    /// Largest balance a recipient may hold after a `controlled_transfer`
+    pub max_wallet_balance: Option<u64>,
+    /// Latest configured rate; a Token-2022 mint applies it two epochs later
+    pub transfer_fee_basis_points: u16,
+    /// Ceiling on the fee taken from a single transfer
+    pub maximum_fee: u64,
+    /// Token account fees are paid into; default until `configure_transfer_fee`
+    pub fee_collector: Pubkey,
+    /// Most a non-whitelisted wallet may send in 24 hours
+    pub velocity_limit: Option<u64>,
+    /// Most a whitelisted wallet may send in 24 hours
+    pub whitelisted_velocity_limit: Option<u64>,
+    /// Token accounts currently frozen, including those carried over by
+    /// `migrate_mint_authority`
+    pub frozen_accounts: u64,
+}
+
+/// `TokenInfo` as written before `migrate_mint_authority` existed; every
+/// later field is appended after `blacklist_enabled`.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct LegacyTokenInfo {
+    authority: Pubkey,
+    mint: Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+    decimals: u8,
+    total_supply: u64,
+    is_initialized: bool,
+    whitelist_enabled: bool,
+    trading_enabled: bool,
+    blacklist_enabled: bool,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct BlacklistEntry {
+    pub wallet: Pubkey,
+    pub is_blacklisted: bool,
+    pub added_at: i64,
+    #[max_len(MAX_BLACKLIST_REASON_LEN)]
+    pub reason: String,
+    pub reason_code: u8,
+    pub expires_at: Option<i64>,
+    /// Hash of the off-chain case file backing this entry
+    pub reference_hash: [u8; 32],
+    pub appeal: AppealStatus,
+}
+
+impl BlacklistEntry {
+    /// An entry stops blocking once it expires or once an appeal against it
+    /// goes unanswered past its deadline.
+    pub fn is_active(&self, now: i64) -> bool {
+        if !self.is_blacklisted {
+            return false;
+        }
+        if self.expires_at.is_some_and(|expires_at| now >= expires_at) {
+            return false;
+        }
+        !matches!(self.appeal, AppealStatus::Pending { deadline, .. } if now > deadline)
+    }
+}
+
+/// Merkle proofs for `controlled_transfer`. Empty whitelist proofs and
+/// missing exclusion proofs are fine while the matching root is unset.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct TransferProofs {
+    pub sender_whitelist: Vec<[u8; 32]>,
+    pub recipient_whitelist: Vec<[u8; 32]>,
+    pub sender_exclusion: Option<ExclusionProof>,
+    pub recipient_exclusion: Option<ExclusionProof>,
+}
+
+/// Shows a wallet is absent from the sorted blacklist tree: `lower` sits at
+/// leaf `index`, `upper` at `index + 1`, and the wallet lies strictly
+/// between them. The tree always starts and ends with the all-zero and
+/// all-0xff keys, so every unlisted wallet has two neighbours.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ExclusionProof {
+    pub index: u32,
+    pub lower: Pubkey,
+    pub lower_proof: Vec<[u8; 32]>,
+    pub upper: Pubkey,
+    pub upper_proof: Vec<[u8; 32]>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum AppealStatus {
+    None,
+    Pending { deadline: i64, evidence_hash: [u8; 32] },
+    Upheld,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct WhitelistEntry {
+    pub wallet: Pubkey,
+    pub is_whitelisted: bool,
+    pub allocation: u64,
+    pub purchased: u64,
+    pub added_at: i64,
+}
+
+/// Tokenomics categories from the published distribution table.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum BucketCategory {
+    Presale,
+    Liquidity,
+    Rewards,
+    Team,
+    Marketing,
+}
+
+impl BucketCategory {
+    pub const ALL: [BucketCategory; 5] = [
+        BucketCategory::Presale,
+        BucketCategory::Liquidity,
+        BucketCategory::Rewards,
+        BucketCategory::Team,
+        BucketCategory::Marketing,
+    ];
+}
+
+/// Marks a token account as exempt from `max_tx_amount` and
+/// `max_wallet_balance`.
+#[account]
+#[derive(InitSpace)]
+pub struct TransferLimitExemption {
+    pub mint: Pubkey,
+    pub account: Pubkey,
+    pub added_by: Pubkey,
+    pub added_at: i64,
+    pub bump: u8,
+}
+
+/// Marks a token account as exempt from the `controlled_transfer` fee. The
+/// Token-2022 fee extension withholds on every transfer and ignores it.
+#[account]
+#[derive(InitSpace)]
+pub struct TransferFeeExemption {
+    pub mint: Pubkey,
+    pub account: Pubkey,
+    pub added_by: Pubkey,
+    pub added_at: i64,
+    pub bump: u8,
+}
+
+/// Rolling 24-hour outflow of one wallet, bucketed by hour. The amount sent
+/// in hour `h` sits in `hourly_outflow[h % VELOCITY_WINDOW_HOURS]`; buckets
+/// that fall out of the window are cleared on the next transfer.
+#[account]
+#[derive(InitSpace)]
+pub struct VelocityTracker {
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+    /// Latest hour (unix time / 3600) a transfer was recorded in
+    pub last_hour: i64,
+    pub hourly_outflow: [u64; VELOCITY_WINDOW_HOURS],
+}
+
+impl VelocityTracker {
+    /// Moves the window up to `hour`, adds `amount` to it and returns the
+    /// total sent over the window.
+    pub fn record(&mut self, hour: i64, amount: u64) -> Result<u64> {
+        let window = VELOCITY_WINDOW_HOURS as i64;
+        let expired = hour.saturating_sub(self.last_hour).clamp(0, window);
+        for offset in 1..=expired {
+            self.hourly_outflow[((self.last_hour + offset) % window) as usize] = 0;
+        }
+        self.last_hour = self.last_hour.max(hour);
+
+        let bucket = &mut self.hourly_outflow[(hour % window) as usize];
+        *bucket = bucket.checked_add(amount).ok_or(TokenError::MathOverflow)?;
+        self.hourly_outflow
+            .iter()
+            .try_fold(0u64, |total, outflow| total.checked_add(*outflow))
+            .ok_or(TokenError::MathOverflow.into())
+    }
+}
+
+/// LP tokens held by the program until `unlock_ts`. The unlock time only
+/// ever moves later.
+#[account]
+#[derive(InitSpace)]
+pub struct LiquidityLock {
+    pub mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub vault: Pubkey,
+    /// Total deposited over the lock's life
+    pub deposited: u64,
+    pub unlock_ts: i64,
+    pub released: bool,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LiquidityLockSummary {
+    pub lp_mint: Pubkey,
+    pub vault: Pubkey,
+    pub amount: u64,
+    pub deposited: u64,
+    pub unlock_ts: i64,
+    pub unlocked: bool,
+    pub released: bool,
+}
+
+/// A token account drains may pull from, and how much has left it this
+/// epoch. The pool itself is owned by the timelock PDA until it is
+/// deregistered.
+#[account]
+#[derive(InitSpace)]
+pub struct TreasuryPool {
+    pub mint: Pubkey,
+    pub pool: Pubkey,
+    /// Most that may be drained from `pool` in one epoch
+    pub epoch_cap: u64,
+    pub epoch: u64,
+    pub epoch_outflow: u64,
+    pub bump: u8,
+}
+
+/// Supply reserved for one category. Tokens can only be minted to
+/// `destination`, up to what has been released, and never beyond `cap`.
+#[account]
+#[derive(InitSpace)]
+pub struct AllocationBucket {
+    pub mint: Pubkey,
+    pub category: BucketCategory,
+    pub cap: u64,
+    /// Token account that receives this bucket's mints
+    pub destination: Pubkey,
+    pub minted: u64,
+    /// Portion of `cap` unlocked for minting so far
+    pub released: u64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BucketSummary {
+    pub category: BucketCategory,
+    pub cap: u64,
+    pub destination: Pubkey,
+    pub minted: u64,
+    pub released: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum Role {
+    Minter,
+    Burner,
+    ComplianceOfficer,
+    Pauser,
+    Treasurer,
+    RoleAdmin,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RoleGrant {
+    pub mint: Pubkey,
+    pub holder: Pubkey,
+    pub role: Role,
+    pub granted_by: Pubkey,
+    pub granted_at: i64,
+    pub expires_at: Option<i64>,
+    pub bump: u8,
+}
+
+impl RoleGrant {
+    pub fn check_active(&self) -> Result<()> {
+        if let Some(expires_at) = self.expires_at {
+            require!(
+                Clock::get()?.unix_timestamp < expires_at,
+                TokenError::RoleExpired
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Delay, in seconds, between queueing and executing each sensitive action.
+/// A zero delay leaves the direct instruction usable. Drains always wait at
+/// least `MIN_DRAIN_LIQUIDITY_DELAY` and tighter transfer limits at least
+/// `MIN_TRANSFER_LIMITS_DELAY`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct TimelockDelays {
+    pub toggle_trading: i64,
+    pub drain_liquidity: i64,
+    pub mint_tokens: i64,
+    pub transfer_authority: i64,
+    pub transfer_limits: i64,
+}
+
+impl TimelockDelays {
+    pub fn get(&self, kind: TimelockKind) -> i64 {
+        let delay = match kind {
+            TimelockKind::ToggleTrading => self.toggle_trading,
+            TimelockKind::DrainLiquidity => self.drain_liquidity,
+            TimelockKind::MintTokens => self.mint_tokens,
+            TimelockKind::TransferAuthority => self.transfer_authority,
+            TimelockKind::TransferLimits => self.transfer_limits,
+        };
+        delay.max(kind.min_delay())
+    }
+
+    fn set(&mut self, kind: TimelockKind, delay: i64) {
+        match kind {
+            TimelockKind::ToggleTrading => self.toggle_trading = delay,
+            TimelockKind::DrainLiquidity => self.drain_liquidity = delay,
+            TimelockKind::MintTokens => self.mint_tokens = delay,
+            TimelockKind::TransferAuthority => self.transfer_authority = delay,
+            TimelockKind::TransferLimits => self.transfer_limits = delay,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum TimelockKind {
+    ToggleTrading,
+    DrainLiquidity,
+    MintTokens,
+    TransferAuthority,
+    TransferLimits,
+}
+
+impl TimelockKind {
+    pub fn min_delay(&self) -> i64 {
+        match self {
+            TimelockKind::DrainLiquidity => MIN_DRAIN_LIQUIDITY_DELAY,
+            TimelockKind::TransferLimits => MIN_TRANSFER_LIMITS_DELAY,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub enum TimelockedAction {
+    ToggleTrading { enabled: bool },
+    MintTokens { amount: u64, destination: Pubkey },
+    DrainLiquidity { amount: u64, liquidity_pool: Pubkey, treasury: Pubkey },
+    ProposeAuthority { new_authority: Pubkey },
+    SetTimelockDelay { kind: TimelockKind, delay: i64 },
+    SetTransferLimits { max_tx_amount: Option<u64>, max_wallet_balance: Option<u64> },
+    SetVelocityLimits { velocity_limit: Option<u64>, whitelisted_velocity_limit: Option<u64> },
+}
+
+impl TimelockedAction {
+    /// `None` means only the token authority may queue the action.
+    pub fn required_role(&self) -> Option<Role> {
+        match self {
+            TimelockedAction::ToggleTrading { .. } => Some(Role::Pauser),
+            TimelockedAction::MintTokens { .. } => Some(Role::Minter),
+            TimelockedAction::DrainLiquidity { .. } => Some(Role::Treasurer),
+            TimelockedAction::ProposeAuthority { .. } => None,
+            TimelockedAction::SetTimelockDelay { .. } => None,
+            TimelockedAction::SetTransferLimits { .. } => None,
+            TimelockedAction::SetVelocityLimits { .. } => None,
+        }
+    }
+
+    /// Lowering a delay waits out the delay being lowered.
+    pub fn kind(&self) -> TimelockKind {
+        match self {
+            TimelockedAction::ToggleTrading { .. } => TimelockKind::ToggleTrading,
+            TimelockedAction::MintTokens { .. } => TimelockKind::MintTokens,
+            TimelockedAction::DrainLiquidity { .. } => TimelockKind::DrainLiquidity,
+            TimelockedAction::ProposeAuthority { .. } => TimelockKind::TransferAuthority,
+            TimelockedAction::SetTimelockDelay { kind, .. } => *kind,
+            TimelockedAction::SetTransferLimits { .. }
+            | TimelockedAction::SetVelocityLimits { .. } => TimelockKind::TransferLimits,
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct QueuedAction {
+    pub mint: Pubkey,
+    pub index: u64,
+    pub action: TimelockedAction,
+    pub queued_by: Pubkey,
+    pub queued_at: i64,
+    pub eta: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AdminCouncil {
+    pub mint: Pubkey,
+    #[max_len(MAX_COUNCIL_MEMBERS)]
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+    pub proposal_count: u64,
+    pub bump: u8,
+    pub authority_bump: u8,
+}
+
+impl AdminCouncil {
+    pub fn is_member(&self, key: &Pubkey) -> bool {
+        self.members.contains(key)
+    }
+
+    /// Drops approvals from keys that have since left the council, so they
+    /// neither count nor hold room a current member needs.
+    pub fn prune_approvals(&self, approvals: &mut Vec<Pubkey>) {
+        approvals.retain(|key| self.is_member(key));
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CouncilProposal {
+    pub council: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    #[max_len(MAX_PROPOSAL_DATA_LEN)]
+    pub data: Vec<u8>,
+    #[max_len(MAX_PROPOSAL_ACCOUNTS)]
+    pub accounts: Vec<ProposalAccountMeta>,
+    #[max_len(MAX_COUNCIL_MEMBERS)]
+    pub approvals: Vec<Pubkey>,
+    pub executed: bool,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct ProposalAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+// ============= HELPERS =============
+
+fn require_role(
+    grant: Option<&Account<RoleGrant>>,
+    mint: Pubkey,
+    holder: Pubkey,
+    role: Role,
+) -> Result<()> {
+    let grant = grant.ok_or(TokenError::MissingRole)?;
+    require!(
+        grant.mint == mint && grant.holder == holder && grant.role == role,
+        TokenError::MissingRole
+    );
+    grant.check_active()
+}
+
+fn authorize_role_admin(
+    token_info: &TokenInfo,
+    admin: Pubkey,
+    admin_grant: Option<&Account<RoleGrant>>,
+    role: Role,
+) -> Result<()> {
+    if admin == token_info.authority {
+        return Ok(());
+    }
+    require!(role != Role::RoleAdmin, TokenError::UnauthorizedAccess);
+    require_role(admin_grant, token_info.mint, admin, Role::RoleAdmin)
+}
+
+fn init_token_info(
+    token_info: &mut TokenInfo,
+    authority: Pubkey,
+    mint: Pubkey,
+    decimals: u8,
+    max_supply: u64,
+    mint_authority_bump: u8,
+) -> Result<()> {
+    require!(max_supply > 0, TokenError::InvalidMaxSupply);
+    require!(decimals <= MAX_DECIMALS, TokenError::InvalidDecimals);
+
+    token_info.authority = authority;
+    token_info.mint = mint;
+    token_info.decimals = decimals;
+    token_info.total_supply = 0;
+    token_info.is_initialized = true;
+    token_info.whitelist_enabled = false;
+    token_info.trading_enabled = true;
+    token_info.blacklist_enabled = true;
+    token_info.pending_authority = None;
+    token_info.mint_authority_bump = mint_authority_bump;
+    token_info.max_supply = max_supply;
+    token_info.minting_renounced = false;
+    token_info.timelock_delays = TimelockDelays {
+        drain_liquidity: MIN_DRAIN_LIQUIDITY_DELAY,
+        transfer_limits: MIN_TRANSFER_LIMITS_DELAY,
+        ..TimelockDelays::default()
+    };
+    token_info.queued_action_count = 0;
+    token_info.appeal_window = DEFAULT_APPEAL_WINDOW;
+    token_info.whitelist_root = [0u8; 32];
+    token_info.whitelist_root_version = 0;
+    token_info.blacklist_root = [0u8; 32];
+    token_info.blacklist_root_version = 0;
+    token_info.bucket_caps_total = 0;
+    token_info.treasury = Pubkey::default();
+    token_info.max_tx_amount = None;
+    token_info.max_wallet_balance = None;
+    token_info.transfer_fee_basis_points = 0;
+    token_info.maximum_fee = 0;
+    token_info.fee_collector = Pubkey::default();
+    token_info.velocity_limit = None;
+    token_info.whitelisted_velocity_limit = None;
+    token_info.frozen_accounts = 0;
+    Ok(())
+}
+
+/// Creates the Token-2022 mint with its transfer hook and transfer fee
+/// extensions. The mint authority PDA holds every authority on it.
+fn create_token_2022_mint<'info>(
+    accounts: &InitializeToken2022<'info>,
+    decimals: u8,
+    transfer_fee_basis_points: u16,
+    maximum_fee: u64,
+) -> Result<()> {
+    let space = ExtensionType::try_calculate_account_len::<SplMint>(&[
+        ExtensionType::TransferHook,
+        ExtensionType::TransferFeeConfig,
+    ])?;
+    let mint = accounts.mint.to_account_info();
+    let token_program = accounts.token_program.to_account_info();
+    let mint_authority = accounts.mint_authority.key();
+
+    let cpi_accounts = anchor_lang::system_program::CreateAccount {
+        from: accounts.authority.to_account_info(),
+        to: mint.clone(),
+    };
+    anchor_lang::system_program::create_account(
+        CpiContext::new(accounts.system_program.to_account_info(), cpi_accounts),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        token_program.key,
+    )?;
+
+    let cpi_accounts = TransferHookInitialize {
+        token_program_id: token_program.clone(),
+        mint: mint.clone(),
+    };
+    transfer_hook_initialize(
+        CpiContext::new(token_program.clone(), cpi_accounts),
+        Some(mint_authority),
+        Some(crate::ID),
+    )?;
+
+    let cpi_accounts = TransferFeeInitialize {
+        token_program_id: token_program.clone(),
+        mint: mint.clone(),
+    };
+    transfer_fee_initialize(
+        CpiContext::new(token_program.clone(), cpi_accounts),
+        Some(&mint_authority),
+        Some(&mint_authority),
+        transfer_fee_basis_points,
+        maximum_fee,
+    )?;
+
+    initialize_mint2(
+        CpiContext::new(token_program, InitializeMint2 { mint }),
+        decimals,
+        &mint_authority,
+        Some(&mint_authority),
+    )
+}
+
+/// Creates the Metaplex metadata account with the mint authority PDA as both
+/// mint and update authority.
+fn create_token_metadata<'info>(
+    cpi_accounts: CreateMetadataAccountsV3<'info>,
+    token_metadata_program: AccountInfo<'info>,
+    mint_authority_bump: u8,
+    name: &str,
+    symbol: &str,
+    uri: &str,
+) -> Result<()> {
+    let mint_key = cpi_accounts.mint.key();
+    let seeds = &[
+        MINT_AUTHORITY_SEED,
+        mint_key.as_ref(),
+        &[mint_authority_bump],
+    ];
+    let signer = &[&seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(token_metadata_program, cpi_accounts, signer);
+    metadata::create_metadata_accounts_v3(
+        cpi_ctx,
+        token_metadata_data(name, symbol, uri),
+        true,
+        true,
+        None,
+    )
+}
+
+/// Wallets and proofs checked against the Merkle roots in TokenInfo.
+struct MerkleWitness<'a> {
+    sender: Pubkey,
+    recipient: Pubkey,
+    proofs: &'a TransferProofs,
+}
+
+/// Trading, blacklist and whitelist checks shared by `controlled_transfer`
+/// and the Token-2022 transfer hook. A missing or expired blacklist entry
+/// means "not listed"; a missing whitelist entry fails while whitelist mode
+/// is on. The hook passes no `witness`; roots cannot be set on its mints.
+fn check_transfer_policy(
+    token_info: &TokenInfo,
+    sender_blacklist: Option<&BlacklistEntry>,
+    recipient_blacklist: Option<&BlacklistEntry>,
+    sender_whitelist: Option<&WhitelistEntry>,
+    recipient_whitelist: Option<&WhitelistEntry>,
+    witness: Option<&MerkleWitness>,
+) -> Result<()> {
+    require!(token_info.trading_enabled, TokenError::TradingDisabled);
+
+    if token_info.blacklist_enabled {
+        let now = Clock::get()?.unix_timestamp;
+        if let Some(sender_blacklist) = sender_blacklist {
+            require!(!sender_blacklist.is_active(now), TokenError::SenderBlacklisted);
+        }
+        if let Some(recipient_blacklist) = recipient_blacklist {
+            require!(!recipient_blacklist.is_active(now), TokenError::RecipientBlacklisted);
+        }
+
+        let root = &token_info.blacklist_root;
+        if let (false, Some(witness)) = (root == &[0u8; 32], witness) {
+            require!(
+                witness.proofs.sender_exclusion.as_ref().is_some_and(|proof| {
+                    verify_exclusion(root, &witness.sender, proof)
+                }),
+                TokenError::InvalidExclusionProof
+            );
+            require!(
+                witness.proofs.recipient_exclusion.as_ref().is_some_and(|proof| {
+                    verify_exclusion(root, &witness.recipient, proof)
+                }),
+                TokenError::InvalidExclusionProof
+            );
+        }
+    }
+
+    if token_info.whitelist_enabled {
+        let root = &token_info.whitelist_root;
+        let on_root = |wallet: &Pubkey, proof: &[[u8; 32]]| {
+            root != &[0u8; 32] && !proof.is_empty() && verify_membership(root, wallet, proof)
+        };
+        require!(
+            sender_whitelist.is_some_and(|entry| entry.is_whitelisted)
+                || witness.is_some_and(|w| on_root(&w.sender, &w.proofs.sender_whitelist)),
+            TokenError::SenderNotWhitelisted
+        );
+        require!(
+            recipient_whitelist.is_some_and(|entry| entry.is_whitelisted)
+                || witness.is_some_and(|w| on_root(&w.recipient, &w.proofs.recipient_whitelist)),
+            TokenError::RecipientNotWhitelisted
+        );
+    }
+
+    Ok(())
+}
+
+/// Same tree as the presale whitelist: keccak leaves, pairs hashed in sorted
+/// order, so proofs need no positions.
+fn verify_membership(root: &[u8; 32], wallet: &Pubkey, proof: &[[u8; 32]]) -> bool {
+    let mut computed = keccak::hashv(&[wallet.as_ref()]).0;
+    for node in proof {
+        let (left, right) = if computed <= *node {
+            (computed, *node)
+        } else {
+            (*node, computed)
+        };
+        computed = keccak::hashv(&[left.as_ref(), right.as_ref()]).0;
+    }
+    &computed == root
+}
+
+/// Positional tree over sorted keys, with domain-separated leaves and nodes
+/// so an inner node can never pass as a leaf.
+fn verify_exclusion(root: &[u8; 32], wallet: &Pubkey, proof: &ExclusionProof) -> bool {
+    let depth = proof.lower_proof.len();
+    if depth != proof.upper_proof.len() || depth > 32 {
+        return false;
+    }
+    let lower_index = proof.index as u64;
+    let upper_index = lower_index + 1;
+    if upper_index >> depth != 0 {
+        return false;
+    }
+    proof.lower < *wallet
+        && *wallet < proof.upper
+        && verify_positional(root, &proof.lower, lower_index, &proof.lower_proof)
+        && verify_positional(root, &proof.upper, upper_index, &proof.upper_proof)
+}
+
+fn verify_positional(root: &[u8; 32], key: &Pubkey, index: u64, proof: &[[u8; 32]]) -> bool {
+    let mut computed = keccak::hashv(&[&[0u8], key.as_ref()]).0;
+    for (level, node) in proof.iter().enumerate() {
+        computed = if (index >> level) & 1 == 0 {
+            keccak::hashv(&[&[1u8], computed.as_ref(), node.as_ref()]).0
+        } else {
+            keccak::hashv(&[&[1u8], node.as_ref(), computed.as_ref()]).0
+        };
+    }
+    &computed == root
+}
+
+/// Token-2022 mints run `transfer_hook`, which cannot carry proofs, so Merkle
+/// lists would silently go unenforced there.
+fn require_merkle_lists_supported(mint: &AccountInfo) -> Result<()> {
+    require!(mint.owner != &Token2022::id(), TokenError::MerkleListsUnsupported);
+    Ok(())
+}
+
+/// Reads a blacklist or whitelist entry from its (already seed-checked) PDA.
+/// Only an address that was never created, i.e. empty and owned by the
+/// system program, reads as "no entry".
+fn load_compliance_entry<T: AccountDeserialize>(account: &AccountInfo) -> Result<Option<T>> {
+    if account.data_is_empty() && account.owner == &System::id() {
+        return Ok(None);
+    }
+    require_keys_eq!(*account.owner, crate::ID, TokenError::InvalidComplianceAccount);
+    let data = account.try_borrow_data()?;
+    Ok(Some(T::try_deserialize(&mut &data[..])?))
+}
+
+fn load_legacy_token_info(account: &AccountInfo) -> Result<LegacyTokenInfo> {
+    require_keys_eq!(*account.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    // Accounts in the current layout are already full size
+    require!(
+        account.data_len() < 8 + TokenInfo::INIT_SPACE,
+        TokenError::AlreadyMigrated
+    );
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && data[..8] == *TokenInfo::DISCRIMINATOR,
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    Ok(LegacyTokenInfo::deserialize(&mut &data[8..])?)
+}
+
+/// Counts the token accounts passed to `migrate_mint_authority`. Each one
+/// must be a frozen account of `mint`, listed once.
+fn count_frozen_accounts(accounts: &[AccountInfo], mint: &Pubkey, token_program: &Pubkey) -> Result<u64> {
+    for (index, account) in accounts.iter().enumerate() {
+        require_keys_eq!(*account.owner, *token_program, TokenError::InvalidFrozenAccount);
+        require!(
+            !accounts[..index].iter().any(|seen| seen.key == account.key),
+            TokenError::InvalidFrozenAccount
+        );
+        let data = account.try_borrow_data()?;
+        let state = StateWithExtensions::<SplTokenAccount>::unpack(&data)?;
+        require!(
+            state.base.mint == *mint && state.base.is_frozen(),
+            TokenError::InvalidFrozenAccount
+        );
+    }
+    Ok(accounts.len() as u64)
+}
+
+fn check_batch_len(wallets: usize, accounts: usize) -> Result<()> {
+    require!(wallets > 0, TokenError::EmptyBatch);
+    require!(wallets == accounts, TokenError::BatchLengthMismatch);
+    Ok(())
+}
+
+/// Checks that `account` is this program's PDA for `seeds` and returns its bump.
+fn verify_compliance_pda(account: &AccountInfo, seeds: &[&[u8]]) -> Result<u8> {
+    let (expected, bump) = Pubkey::find_program_address(seeds, &crate::ID);
+    require_keys_eq!(account.key(), expected, TokenError::InvalidComplianceAccount);
+    Ok(bump)
+}
+
+/// Batch counterpart of `init_if_needed` for compliance entries: creates the
+/// PDA at `seeds` when missing, otherwise grows it to `space` if needed.
+fn open_compliance_entry<'info>(
+    account: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    space: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let bump = verify_compliance_pda(account, seeds)?;
+    if account.owner == &crate::ID {
+        return grow_account(account, payer, system_program, space);
+    }
+    require!(
+        account.owner == &System::id() && account.data_is_empty(),
+        TokenError::InvalidComplianceAccount
+    );
+
+    let bump = [bump];
+    let mut signer_seeds = seeds.to_vec();
+    signer_seeds.push(&bump);
+    let signer = &[&signer_seeds[..]];
+
+    let rent = Rent::get()?.minimum_balance(space);
+    if account.lamports() == 0 {
+        let cpi_accounts = anchor_lang::system_program::CreateAccount {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(system_program.clone(), cpi_accounts, signer);
+        anchor_lang::system_program::create_account(cpi_ctx, rent, space as u64, &crate::ID)?;
+        return Ok(());
+    }
+
+    // Someone pre-funded the address, so create_account would fail
+    let shortfall = rent.saturating_sub(account.lamports());
+    if shortfall > 0 {
+        let cpi_accounts = anchor_lang::system_program::Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        let cpi_ctx = CpiContext::new(system_program.clone(), cpi_accounts);
+        anchor_lang::system_program::transfer(cpi_ctx, shortfall)?;
+    }
+    let cpi_accounts = anchor_lang::system_program::Allocate {
+        account_to_allocate: account.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(system_program.clone(), cpi_accounts, signer);
+    anchor_lang::system_program::allocate(cpi_ctx, space as u64)?;
+    let cpi_accounts = anchor_lang::system_program::Assign {
+        account_to_assign: account.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(system_program.clone(), cpi_accounts, signer);
+    anchor_lang::system_program::assign(cpi_ctx, &crate::ID)?;
+    Ok(())
+}
+
+/// Counts `amount` against the recipient's whitelist allocation while
+/// whitelist mode is on. Wallets without an active entry are not tracked.
+fn record_whitelist_receipt(
+    token_info: &TokenInfo,
+    recipient_whitelist: &AccountInfo,
+    amount: u64,
+) -> Result<()> {
+    if !token_info.whitelist_enabled {
+        return Ok(());
+    }
+    let Some(mut entry) = load_compliance_entry::<WhitelistEntry>(recipient_whitelist)? else {
+        return Ok(());
+    };
+    if !entry.is_whitelisted {
+        return Ok(());
+    }
+
+    entry.purchased = entry
+        .purchased
+        .checked_add(amount)
+        .ok_or(TokenError::MathOverflow)?;
+    require!(
+        entry.purchased <= entry.allocation,
+        TokenError::AllocationExceeded
+    );
+    entry.try_serialize(&mut &mut recipient_whitelist.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+/// Rejects direct calls to the hook; Token-2022 sets `transferring` on the
+/// source account only for the duration of a transfer.
+fn assert_is_transferring(source_token: &AccountInfo) -> Result<()> {
+    let data = source_token.try_borrow_data()?;
+    let account = StateWithExtensions::<SplTokenAccount>::unpack(&data)?;
+    let extension = account.get_extension::<TransferHookAccount>()?;
+    require!(bool::from(extension.transferring), TokenError::NotTransferring);
+    Ok(())
+}
+
+/// Accounts Token-2022 appends to the hook's execute instruction, in the
+/// order `TransferHook` expects them. Indexes refer to the execute
+/// accounts: 0 source, 1 mint, 2 destination; data offset 32 of a token
+/// account is its owner.
+fn transfer_hook_extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+    let owner_of = |account_index: u8| Seed::AccountData {
+        account_index,
+        data_index: 32,
+        length: 32,
+    };
+    let entry = |prefix: &[u8], account_index: u8, is_writable: bool| {
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: prefix.to_vec() },
+                Seed::AccountKey { index: 1 },
+                owner_of(account_index),
+            ],
+            false,
+            is_writable,
+        )
+    };
+
+    Ok(vec![
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"token_info".to_vec() },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            false,
+        )?,
+        entry(b"blacklist", 0, false)?,
+        entry(b"blacklist", 2, false)?,
+        entry(b"whitelist", 0, false)?,
+        // Written when the recipient's allocation is counted
+        entry(b"whitelist", 2, true)?,
+    ])
+}
+
+/// Grows an account created under an older, smaller layout so the current
+/// layout fits, topping up rent from `payer`.
+fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    if account.data_len() >= new_len {
+        return Ok(());
+    }
+    let shortfall = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if shortfall > 0 {
+        let cpi_accounts = anchor_lang::system_program::Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        let cpi_ctx = CpiContext::new(system_program.clone(), cpi_accounts);
+        anchor_lang::system_program::transfer(cpi_ctx, shortfall)?;
+    }
+    account.resize(new_len)?;
+    Ok(())
+}
+
+fn validate_metadata(name: &str, symbol: &str, uri: &str) -> Result<()> {
+    require!(
+        name.len() <= MAX_NAME_LEN && symbol.len() <= MAX_SYMBOL_LEN && uri.len() <= MAX_URI_LEN,
+        TokenError::MetadataTooLong
+    );
+    Ok(())
+}
+
+fn token_metadata_data(name: &str, symbol: &str, uri: &str) -> DataV2 {
+    DataV2 {
+        name: name.to_string(),
+        symbol: symbol.to_string(),
+        uri: uri.to_string(),
+        seller_fee_basis_points: 0,
+        creators: None,
+        collection: None,
+        uses: None,
+    }
+}
+
+fn mint_with_pda<'info>(
+    token_info: &mut Account<'info, TokenInfo>,
+    mint: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    mint_authority: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    require!(!token_info.minting_renounced, TokenError::MintingRenounced);
+    let new_supply = token_info
+        .total_supply
+        .checked_add(amount)
+        .ok_or(TokenError::MathOverflow)?;
+    require!(
+        new_supply <= token_info.max_supply,
+        TokenError::MaxSupplyExceeded
+    );
+
+    let mint_key = mint.key();
+    let seeds = &[
+        MINT_AUTHORITY_SEED,
+        mint_key.as_ref(),
+        &[token_info.mint_authority_bump],
+    ];
+    let signer = &[&seeds[..]];
+    let destination_key = destination.key();
+    let cpi_accounts = MintTo {
+        mint,
+        to: destination,
+        authority: mint_authority,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer);
+
+    token_interface::mint_to(cpi_ctx, amount)?;
+
+    token_info.total_supply = new_supply;
+
+    emit!(TokensMinted {
+        mint: mint_key,
+        to: destination_key,
+        amount,
+        new_supply: token_info.total_supply,
+    });
+
+    Ok(())
+}
+
+fn record_bucket_mint(bucket: &mut AllocationBucket, amount: u64) -> Result<()> {
+    let minted = bucket
+        .minted
+        .checked_add(amount)
+        .ok_or(TokenError::MathOverflow)?;
+    require!(minted <= bucket.released, TokenError::BucketReleaseExceeded);
+    bucket.minted = minted;
+    Ok(())
+}
+
+/// Counts a drain against the pool's cap, starting a fresh allowance when
+/// the epoch has rolled over.
+fn record_treasury_outflow(treasury_pool: &mut TreasuryPool, amount: u64, epoch: u64) -> Result<()> {
+    if treasury_pool.epoch != epoch {
+        treasury_pool.epoch = epoch;
+        treasury_pool.epoch_outflow = 0;
+    }
+    let epoch_outflow = treasury_pool
+        .epoch_outflow
+        .checked_add(amount)
+        .ok_or(TokenError::MathOverflow)?;
+    require!(
+        epoch_outflow <= treasury_pool.epoch_cap,
+        TokenError::EpochOutflowCapExceeded
+    );
+    treasury_pool.epoch_outflow = epoch_outflow;
+    Ok(())
+}
+
+/// Enforces the anti-whale limits for `controlled_transfer`: `amount` is
+/// checked against `max_tx_amount`, `received` (after fees) against
+/// `max_wallet_balance`. An exempt sender or recipient lifts
+/// `max_tx_amount`; only an exempt recipient lifts `max_wallet_balance`.
+fn check_transfer_limits(
+    token_info: &TokenInfo,
+    amount: u64,
+    received: u64,
+    recipient_balance: u64,
+    sender_exempt: bool,
+    recipient_exempt: bool,
+) -> Result<()> {
+    if let Some(max_tx_amount) = token_info.max_tx_amount {
+        require!(
+            sender_exempt || recipient_exempt || amount <= max_tx_amount,
+            TokenError::MaxTransactionExceeded
+        );
+    }
+    if let Some(max_wallet_balance) = token_info.max_wallet_balance {
+        let balance_after = recipient_balance
+            .checked_add(received)
+            .ok_or(TokenError::MathOverflow)?;
+        require!(
+            recipient_exempt || balance_after <= max_wallet_balance,
+            TokenError::MaxWalletBalanceExceeded
+        );
+    }
+    Ok(())
+}
+
+/// True when `new` is no stricter than `current`.
+fn is_limit_raise(current: Option<u64>, new: Option<u64>) -> bool {
+    match (current, new) {
+        (_, None) => true,
+        (None, Some(_)) => false,
+        (Some(current), Some(new)) => new >= current,
+    }
+}
+
+fn validate_transfer_limits(max_tx_amount: Option<u64>, max_wallet_balance: Option<u64>) -> Result<()> {
+    require!(
+        max_tx_amount != Some(0) && max_wallet_balance != Some(0),
+        TokenError::InvalidTransferLimit
+    );
+    Ok(())
+}
+
+fn set_transfer_limits(
+    token_info: &mut TokenInfo,
+    max_tx_amount: Option<u64>,
+    max_wallet_balance: Option<u64>,
+    authority: Pubkey,
+) -> Result<()> {
+    validate_transfer_limits(max_tx_amount, max_wallet_balance)?;
+    token_info.max_tx_amount = max_tx_amount;
+    token_info.max_wallet_balance = max_wallet_balance;
+
+    emit!(TransferLimitsUpdated {
+        mint: token_info.mint,
+        max_tx_amount,
+        max_wallet_balance,
+        authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+fn set_velocity_limits(
+    token_info: &mut TokenInfo,
+    velocity_limit: Option<u64>,
+    whitelisted_velocity_limit: Option<u64>,
+    authority: Pubkey,
+) -> Result<()> {
+    validate_transfer_limits(velocity_limit, whitelisted_velocity_limit)?;
+    token_info.velocity_limit = velocity_limit;
+    token_info.whitelisted_velocity_limit = whitelisted_velocity_limit;
+
+    emit!(VelocityLimitsUpdated {
+        mint: token_info.mint,
+        velocity_limit,
+        whitelisted_velocity_limit,
+        authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Whitelisted through an active entry or a Merkle proof, whether or not
+/// whitelist mode is on. Picks the sender's velocity tier.
+fn is_sender_whitelisted(
+    token_info: &TokenInfo,
+    sender_whitelist: Option<&WhitelistEntry>,
+    witness: &MerkleWitness,
+) -> bool {
+    let root = &token_info.whitelist_root;
+    sender_whitelist.is_some_and(|entry| entry.is_whitelisted)
+        || (root != &[0u8; 32]
+            && !witness.proofs.sender_whitelist.is_empty()
+            && verify_membership(root, &witness.sender, &witness.proofs.sender_whitelist))
+}
+
+fn velocity_limit(token_info: &TokenInfo, sender_whitelisted: bool) -> Option<u64> {
+    if sender_whitelisted {
+        token_info.whitelisted_velocity_limit
+    } else {
+        token_info.velocity_limit
+    }
+}
+
+/// Counts `amount` against the sender's rolling 24-hour outflow. The
+/// tracker is created here the first time a limit applies to the wallet,
+/// so senders without a limit never pay for one.
+fn record_velocity<'info>(
+    token_info: &TokenInfo,
+    tracker: &AccountInfo<'info>,
+    wallet: Pubkey,
+    amount: u64,
+    limit: u64,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let existing = load_compliance_entry::<VelocityTracker>(tracker)?;
+    open_compliance_entry(
+        tracker,
+        &[VELOCITY_TRACKER_SEED, token_info.mint.as_ref(), wallet.as_ref()],
+        8 + VelocityTracker::INIT_SPACE,
+        payer,
+        system_program,
+    )?;
+    let mut velocity = existing.unwrap_or(VelocityTracker {
+        mint: token_info.mint,
+        wallet,
+        last_hour: 0,
+        hourly_outflow: [0; VELOCITY_WINDOW_HOURS],
+    });
+
+    let now = Clock::get()?.unix_timestamp;
+    let outflow = velocity.record(now / SECONDS_PER_HOUR, amount)?;
+    require!(outflow <= limit, TokenError::VelocityLimitExceeded);
+    velocity.try_serialize(&mut &mut tracker.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+fn validate_transfer_fee(transfer_fee_basis_points: u16) -> Result<()> {
+    require!(
+        transfer_fee_basis_points <= MAX_FEE_BASIS_POINTS,
+        TokenError::InvalidTransferFee
+    );
+    Ok(())
+}
+
+/// Fee on a `controlled_transfer` of `amount`: the configured rate, rounded
+/// up and capped at `maximum_fee`, unless either side is exempt.
+fn calculate_transfer_fee(token_info: &TokenInfo, amount: u64, exempt: bool) -> Result<u64> {
+    if exempt || token_info.transfer_fee_basis_points == 0 {
+        return Ok(0);
+    }
+
+    let fee = (amount as u128)
+        .checked_mul(token_info.transfer_fee_basis_points as u128)
+        .and_then(|scaled| scaled.checked_add(MAX_FEE_BASIS_POINTS as u128 - 1))
+        .ok_or(TokenError::MathOverflow)?
+        / MAX_FEE_BASIS_POINTS as u128;
+    Ok((fee as u64).min(token_info.maximum_fee))
+}
+
+/// Fee the Token-2022 transfer fee extension withholds from a transfer of
+/// `amount` in the current epoch.
+fn withheld_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<SplMint>::unpack(&data)?;
+    match state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => Ok(config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(TokenError::MathOverflow)?),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Fees harvested into a Token-2022 mint and not yet withdrawn.
+fn withheld_fees(mint: &AccountInfo) -> Result<u64> {
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<SplMint>::unpack(&data)?;
+    Ok(u64::from(state.get_extension::<TransferFeeConfig>()?.withheld_amount))
+}
+
+fn transfer_from_sender<'info>(
+    accounts: &ControlledTransfer<'info>,
+    destination: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = TransferChecked {
+        from: accounts.from.to_account_info(),
+        mint: accounts.mint.to_account_info(),
+        to: destination,
+        authority: accounts.authority.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, amount, accounts.token_info.decimals)
+}
+
+fn set_trading_enabled(token_info: &mut TokenInfo, enabled: bool, authority: Pubkey) -> Result<()> {
+    token_info.trading_enabled = enabled;
+
+    emit!(TradingToggled {
+        enabled,
+        authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+fn set_pending_authority(token_info: &mut TokenInfo, new_authority: Pubkey) -> Result<()> {
+    token_info.pending_authority = Some(new_authority);
+
+    emit!(AuthorityProposed {
+        current_authority: token_info.authority,
+        pending_authority: new_authority,
+        mint: token_info.mint,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+fn set_timelock_delay(
+    token_info: &mut TokenInfo,
+    kind: TimelockKind,
+    delay: i64,
+    authority: Pubkey,
+) -> Result<()> {
+    let old_delay = token_info.timelock_delays.get(kind);
+    token_info.timelock_delays.set(kind, delay);
+
+    emit!(TimelockDelayUpdated {
+        mint: token_info.mint,
+        kind,
+        old_delay,
+        new_delay: delay,
+        authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+fn validate_council(members: &[Pubkey], threshold: u8) -> Result<()> {
+    require!(
+        !members.is_empty() && members.len() <= MAX_COUNCIL_MEMBERS,
+        TokenError::InvalidCouncilMembers
+    );
+    for (i, member) in members.iter().enumerate() {
+        require!(
+            !members[i + 1..].contains(member),
+            TokenError::InvalidCouncilMembers
+        );
+    }
+    require!(
+        threshold > 0 && threshold as usize <= members.len(),
+        TokenError::InvalidCouncilThreshold
+    );
+    Ok(())
+}
+
+// ============= EVENTS =============
+
+#[event]
+pub struct TokensMinted {
+    pub mint: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub new_supply: u64,
+}
+
+#[event]
+pub struct AllocationBucketCreated {
+    pub mint: Pubkey,
+    pub category: BucketCategory,
+    pub cap: u64,
+    pub destination: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct AllocationBucketReleased {
+    pub mint: Pubkey,
+    pub category: BucketCategory,
+    pub amount: u64,
+    pub released: u64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensBurned {
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub amount: u64,
+    pub new_supply: u64,
+}
+
+#[event]
+pub struct LiquidityDrained {
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub epoch: u64,
+    pub epoch_outflow: u64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct LiquidityLockCreated {
+    pub mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub vault: Pubkey,
+    pub unlock_ts: i64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityDeposited {
+    pub mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub amount: u64,
+    pub deposited: u64,
+    pub unlock_ts: i64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityLockExtended {
+    pub mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub old_unlock_ts: i64,
+    pub new_unlock_ts: i64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityReleased {
+    pub mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasurySet {
+    pub mint: Pubkey,
+    pub treasury: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryPoolRegistered {
+    pub mint: Pubkey,
+    pub pool: Pubkey,
+    pub epoch_cap: u64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryPoolDeregistered {
+    pub mint: Pubkey,
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WalletBlacklisted {
+    pub wallet: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+    pub reason_code: u8,
+    pub reason: String,
+    pub expires_at: Option<i64>,
+    pub reference_hash: [u8; 32],
+}
+
+#[event]
+pub struct WalletUnblacklisted {
+    pub wallet: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BlacklistEntryClosed {
+    pub wallet: Pubkey,
+    pub was_blacklisted: bool,
+    pub reason_code: u8,
+    pub rent_receiver: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AppealFiled {
+    pub wallet: Pubkey,
+    pub evidence_uri: String,
+    pub evidence_hash: [u8; 32],
+    pub deadline: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AppealResolved {
+    pub wallet: Pubkey,
+    pub upheld: bool,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AppealWindowUpdated {
+    pub mint: Pubkey,
+    pub appeal_window: i64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct BlacklistSystemToggled {
+    pub enabled: bool,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WalletWhitelisted {
+    pub wallet: Pubkey,
+    pub allocation: u64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WhitelistAllocationUpdated {
+    pub wallet: Pubkey,
+    pub allocation: u64,
+    pub purchased: u64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WalletRemovedFromWhitelist {
+    pub wallet: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WhitelistEntryClosed {
+    pub wallet: Pubkey,
+    pub was_whitelisted: bool,
+    pub allocation: u64,
+    pub purchased: u64,
+    pub rent_receiver: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WhitelistBatchAdded {
+    pub wallets: Vec<Pubkey>,
+    pub allocations: Vec<u64>,
+    pub authority: Pubkey,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct TransferAuthority<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    pub mint: Account<'info, Mint>,
-    
-    #[account(
-        mut,
-        seeds = [b"token_info", mint.key().as_ref()],
-        bump,
-    )]
-    pub token_info: Account<'info, TokenInfo>,
+#[event]
+pub struct WhitelistBatchRemoved {
+    pub wallets: Vec<Pubkey>,
+    pub authority: Pubkey,
+    pub timestamp: i64,
 }
 
-// ============= DATA STRUCTS =============
+#[event]
+pub struct BlacklistBatchAdded {
+    pub wallets: Vec<Pubkey>,
+    pub reason_code: u8,
+    pub reason: String,
+    pub expires_at: Option<i64>,
+    pub reference_hash: [u8; 32],
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
 
-#[account]
-#[derive(InitSpace)]
-pub struct TokenInfo {
+#[event]
+pub struct BlacklistBatchRemoved {
+    pub wallets: Vec<Pubkey>,
     pub authority: Pubkey,
-    pub mint: Pubkey,
-    #[max_len(32)]
-    pub name: String,
-    #[max_len(10)]
-    pub symbol: String,
-    #[max_len(200)]
-    pub uri: String,
-    pub decimals: u8,
-    pub total_supply: u64,
-    pub is_initialized: bool,
-    pub whitelist_enabled: bool,
-    pub trading_enabled: bool,
-    pub blacklist_enabled: bool,
+    pub timestamp: i64,
 }
 
-#[account]
-#[derive(InitSpace)]
-pub struct BlacklistEntry {
-    pub wallet: Pubkey,
-    pub is_blacklisted: bool,
-    pub added_at: i64,
-    #[max_len(100)]
-    pub reason: String,
+#[event]
+pub struct ListRootUpdated {
+    pub mint: Pubkey,
+    pub blacklist: bool,
+    pub root: [u8; 32],
+    pub version: u32,
+    pub authority: Pubkey,
+    pub timestamp: i64,
 }
 
-#[account]
-#[derive(InitSpace)]
-pub struct WhitelistEntry {
-    pub wallet: Pubkey,
-    pub is_whitelisted: bool,
-    pub allocation: u64,
-    pub purchased: u64,
-    pub added_at: i64,
+#[event]
+pub struct WhitelistModeToggled {
+    pub enabled: bool,
+    pub authority: Pubkey,
+    pub timestamp: i64,
 }
 
-// ============= EVENTS =============
+#[event]
+pub struct AccountFrozen {
+    pub mint: Pubkey,
+    pub account: Pubkey,
+    pub owner: Pubkey,
+    pub reason_code: u8,
+    pub frozen_accounts: u64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
 
 #[event]
-pub struct TokensMinted {
+pub struct AccountThawed {
     pub mint: Pubkey,
-    pub to: Pubkey,
-    pub amount: u64,
-    pub new_supply: u64,
+    pub account: Pubkey,
+    pub owner: Pubkey,
+    pub reason_code: u8,
+    pub frozen_accounts: u64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
 }
 
 #[event]
-pub struct TokensBurned {
+pub struct TransferLimitsUpdated {
     pub mint: Pubkey,
-    pub from: Pubkey,
-    pub amount: u64,
-    pub new_supply: u64,
+    pub max_tx_amount: Option<u64>,
+    pub max_wallet_balance: Option<u64>,
+    pub authority: Pubkey,
+    pub timestamp: i64,
 }
 
 #[event]
-pub struct LiquidityDrained {
-    pub from: Pubkey,
-    pub to: Pubkey,
-    pub amount: u64,
+pub struct VelocityLimitsUpdated {
+    pub mint: Pubkey,
+    pub velocity_limit: Option<u64>,
+    pub whitelisted_velocity_limit: Option<u64>,
     pub authority: Pubkey,
+    pub timestamp: i64,
 }
 
 #[event]
-pub struct WalletBlacklisted {
-    pub wallet: Pubkey,
+pub struct LimitExemptionAdded {
+    pub mint: Pubkey,
+    pub account: Pubkey,
     pub authority: Pubkey,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct WalletUnblacklisted {
-    pub wallet: Pubkey,
+pub struct LimitExemptionRemoved {
+    pub mint: Pubkey,
+    pub account: Pubkey,
     pub authority: Pubkey,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct BlacklistSystemToggled {
+pub struct TradingToggled {
     pub enabled: bool,
     pub authority: Pubkey,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct WalletWhitelisted {
-    pub wallet: Pubkey,
-    pub allocation: u64,
+pub struct TransferFeeConfigured {
+    pub mint: Pubkey,
+    pub transfer_fee_basis_points: u16,
+    pub maximum_fee: u64,
+    pub fee_collector: Pubkey,
     pub authority: Pubkey,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct WalletRemovedFromWhitelist {
-    pub wallet: Pubkey,
+pub struct FeeExemptionAdded {
+    pub mint: Pubkey,
+    pub account: Pubkey,
     pub authority: Pubkey,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct WhitelistModeToggled {
-    pub enabled: bool,
+pub struct FeeExemptionRemoved {
+    pub mint: Pubkey,
+    pub account: Pubkey,
     pub authority: Pubkey,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct TradingToggled {
-    pub enabled: bool,
+pub struct WithheldFeesHarvested {
+    pub mint: Pubkey,
+    pub accounts: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WithheldFeesWithdrawn {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
     pub authority: Pubkey,
     pub timestamp: i64,
 }
@@ -813,10 +5972,53 @@ pub struct TradingToggled {
 pub struct ControlledTransferExecuted {
     pub from: Pubkey,
     pub to: Pubkey,
-    pub amount: u64,
+    pub gross: u64,
+    pub fee: u64,
+    pub net: u64,
     pub authority: Pubkey,
 }
 
+#[event]
+pub struct MintingRenounced {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub final_supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokenMetadataUpdated {
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct MintAuthorityMigrated {
+    pub mint: Pubkey,
+    pub old_mint_authority: Pubkey,
+    pub new_mint_authority: Pubkey,
+    pub freeze_authority_migrated: bool,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub current_authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposalCancelled {
+    pub current_authority: Pubkey,
+    pub cancelled_authority: Pubkey,
+    pub mint: Pubkey,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct AuthorityTransferred {
     pub old_authority: Pubkey,
@@ -824,6 +6026,103 @@ pub struct AuthorityTransferred {
     pub mint: Pubkey,
 }
 
+#[event]
+pub struct RoleGranted {
+    pub mint: Pubkey,
+    pub role: Role,
+    pub holder: Pubkey,
+    pub expires_at: Option<i64>,
+    pub granted_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub mint: Pubkey,
+    pub role: Role,
+    pub holder: Pubkey,
+    pub revoked_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ActionQueued {
+    pub mint: Pubkey,
+    pub queued_action: Pubkey,
+    pub index: u64,
+    pub action: TimelockedAction,
+    pub eta: i64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct QueuedActionExecuted {
+    pub mint: Pubkey,
+    pub queued_action: Pubkey,
+    pub index: u64,
+    pub action: TimelockedAction,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct QueuedActionCancelled {
+    pub mint: Pubkey,
+    pub queued_action: Pubkey,
+    pub index: u64,
+    pub action: TimelockedAction,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TimelockDelayUpdated {
+    pub mint: Pubkey,
+    pub kind: TimelockKind,
+    pub old_delay: i64,
+    pub new_delay: i64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminCouncilUpdated {
+    pub council: Pubkey,
+    pub council_authority: Pubkey,
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CouncilProposalCreated {
+    pub council: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CouncilProposalApproved {
+    pub council: Pubkey,
+    pub proposal: Pubkey,
+    pub member: Pubkey,
+    pub approvals: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CouncilProposalExecuted {
+    pub council: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u64,
+    pub executor: Pubkey,
+    pub approvals: u8,
+    pub timestamp: i64,
+}
+
 // ============= ERRORS =============
 
 #[error_code]
@@ -857,4 +6156,205 @@ pub enum TokenError {
     
     #[msg("Whitelist allocation exceeded")]
     AllocationExceeded,
+    
+    #[msg("No authority handover is pending")]
+    NoPendingAuthority,
+    
+    #[msg("Signer is not the pending authority")]
+    InvalidPendingAuthority,
+    
+    #[msg("Signer is not the current mint authority")]
+    InvalidMintAuthority,
+    
+    #[msg("Math overflow occurred")]
+    MathOverflow,
+    
+    #[msg("Council members must be unique and between 1 and 10")]
+    InvalidCouncilMembers,
+    
+    #[msg("Council threshold must be between 1 and the member count")]
+    InvalidCouncilThreshold,
+    
+    #[msg("Signer is not a council member")]
+    NotCouncilMember,
+    
+    #[msg("Proposal instruction data or account list is too large")]
+    ProposalTooLarge,
+    
+    #[msg("Member already approved this proposal")]
+    ProposalAlreadyApproved,
+    
+    #[msg("Proposal has already been executed")]
+    ProposalAlreadyExecuted,
+    
+    #[msg("Proposal does not have enough approvals")]
+    ProposalThresholdNotMet,
+    
+    #[msg("Provided accounts do not match the proposal")]
+    ProposalAccountsMismatch,
+    
+    #[msg("This action is timelocked and must be queued")]
+    TimelockRequired,
+    
+    #[msg("Queued action is not executable yet")]
+    TimelockNotElapsed,
+    
+    #[msg("Timelock delays can only be lowered through the queue")]
+    TimelockDecreaseRequiresQueue,
+    
+    #[msg("Timelock delay must be between 0 and 30 days")]
+    InvalidTimelockDelay,
+    
+    #[msg("An account required by the queued action is missing or wrong")]
+    MissingActionAccount,
+    
+    #[msg("Signer does not hold the required role")]
+    MissingRole,
+    
+    #[msg("Role grant has expired")]
+    RoleExpired,
+    
+    #[msg("Role expiry must be in the future")]
+    InvalidRoleExpiry,
+    
+    #[msg("Max supply must be greater than zero and cover the minted supply")]
+    InvalidMaxSupply,
+    
+    #[msg("Minting would exceed the max supply")]
+    MaxSupplyExceeded,
+    
+    #[msg("Minting has been permanently renounced")]
+    MintingRenounced,
+    
+    #[msg("Decimals must be between 0 and 9")]
+    InvalidDecimals,
+    
+    #[msg("Token info decimals do not match the mint")]
+    DecimalsMismatch,
+    
+    #[msg("Name, symbol or uri exceeds the metadata length limit")]
+    MetadataTooLong,
+    
+    #[msg("Compliance account is not owned by this program")]
+    InvalidComplianceAccount,
+    
+    #[msg("Transfer hook called outside of a transfer")]
+    NotTransferring,
+    
+    #[msg("Blacklist reason exceeds 100 bytes")]
+    BlacklistReasonTooLong,
+    
+    #[msg("Blacklist expiry must be in the future")]
+    InvalidBlacklistExpiry,
+    
+    #[msg("Wallet is not currently blacklisted")]
+    NotBlacklisted,
+    
+    #[msg("An appeal has already been filed for this entry")]
+    AppealAlreadyFiled,
+    
+    #[msg("No pending appeal for this entry")]
+    NoPendingAppeal,
+    
+    #[msg("The appeal window has elapsed")]
+    AppealWindowElapsed,
+    
+    #[msg("Appeal window must be between 1 second and 90 days")]
+    InvalidAppealWindow,
+    
+    #[msg("Evidence URI exceeds 200 bytes")]
+    EvidenceUriTooLong,
+    
+    #[msg("Batch must contain at least one wallet")]
+    EmptyBatch,
+    
+    #[msg("Batch wallets, values and accounts must have the same length")]
+    BatchLengthMismatch,
+    
+    #[msg("Blacklist root is set; a valid exclusion proof is required")]
+    InvalidExclusionProof,
+    
+    #[msg("Merkle lists are not supported on Token-2022 mints")]
+    MerkleListsUnsupported,
+    
+    #[msg("Allocation bucket caps would exceed max supply")]
+    BucketCapsExceedMaxSupply,
+    
+    #[msg("Release would exceed the bucket cap")]
+    BucketCapExceeded,
+    
+    #[msg("Mint exceeds the bucket's released amount")]
+    BucketReleaseExceeded,
+    
+    #[msg("Destination is not the bucket's destination")]
+    BucketDestinationMismatch,
+    
+    #[msg("Treasury has already been set")]
+    TreasuryAlreadySet,
+    
+    #[msg("Destination is not the registered treasury")]
+    TreasuryMismatch,
+    
+    #[msg("Liquidity pool is not registered")]
+    PoolNotRegistered,
+    
+    #[msg("Drain exceeds the pool's per-epoch outflow cap")]
+    EpochOutflowCapExceeded,
+    
+    #[msg("Unlock time must be in the future")]
+    InvalidUnlockTime,
+    
+    #[msg("Unlock time can only be extended")]
+    UnlockTimeDecrease,
+    
+    #[msg("Liquidity is still locked")]
+    LiquidityStillLocked,
+    
+    #[msg("Liquidity lock has already been released")]
+    LiquidityAlreadyReleased,
+    
+    #[msg("Transfer amount exceeds the max transaction limit")]
+    MaxTransactionExceeded,
+    
+    #[msg("Recipient balance would exceed the max wallet limit")]
+    MaxWalletBalanceExceeded,
+    
+    #[msg("Transfer limits must be greater than zero")]
+    InvalidTransferLimit,
+    
+    #[msg("Adding or lowering a transfer limit requires the timelock queue")]
+    TransferLimitDecreaseRequiresQueue,
+    
+    #[msg("Transfer fee cannot exceed 10000 basis points")]
+    InvalidTransferFee,
+    
+    #[msg("Fee collector does not match the configured account")]
+    FeeCollectorMismatch,
+    
+    #[msg("No withheld fees to withdraw")]
+    NoWithheldFees,
+    
+    #[msg("Transfer exceeds the sender's 24-hour outflow limit")]
+    VelocityLimitExceeded,
+    
+    #[msg("Token account is already frozen")]
+    AccountAlreadyFrozen,
+    
+    #[msg("Token account is not frozen")]
+    AccountNotFrozen,
+    
+    #[msg("Token info is already in the current layout")]
+    AlreadyMigrated,
+    
+    #[msg("Token-2022 mints are transferred directly; the transfer hook runs the checks")]
+    Token2022TransferUnsupported,
+    
+    #[msg("Frozen account count is already zero")]
+    FrozenAccountsUnderflow,
+    
+    #[msg("Treasury pool still holds tokens; drain it before deregistering")]
+    TreasuryPoolNotEmpty,
+    
+    #[msg("Expected each frozen token account of this mint once")]
+    InvalidFrozenAccount,
 }
\ No newline at end of file
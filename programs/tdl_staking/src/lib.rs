@@ -0,0 +1,588 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    spl_token_2022::onchain::invoke_transfer_checked, Mint, TokenAccount, TokenInterface,
+};
+
+declare_id!("4a3dk9Cfwyf1BvpBMyTkqr2AMNGAjuE3KtoBkJsmKeJM");
+
+const POOL_SEED: &[u8] = b"pool";
+const STAKE_SEED: &[u8] = b"stake";
+const STAKE_VAULT_SEED: &[u8] = b"stake_vault";
+const REWARD_VAULT_SEED: &[u8] = b"reward_vault";
+
+// Scales reward_per_token so small rates against a large stake do not round to zero
+const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + StakingPool::INIT_SPACE,
+        seeds = [POOL_SEED, mint.key().as_ref(), admin.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, StakingPool>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [STAKE_VAULT_SEED, pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pool,
+        token::token_program = token_program
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [REWARD_VAULT_SEED, pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pool,
+        token::token_program = token_program
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, mint.key().as_ref(), pool.admin.as_ref()],
+        bump = pool.bump,
+        has_one = mint
+    )]
+    pub pool: Account<'info, StakingPool>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [STAKE_SEED, pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, StakePosition>,
+    #[account(
+        mut,
+        seeds = [STAKE_VAULT_SEED, pool.key().as_ref()],
+        bump = pool.stake_vault_bump
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [REWARD_VAULT_SEED, pool.key().as_ref()],
+        bump = pool.reward_vault_bump
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner,
+        token::token_program = token_program
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    pub owner: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, mint.key().as_ref(), pool.admin.as_ref()],
+        bump = pool.bump,
+        has_one = mint
+    )]
+    pub pool: Account<'info, StakingPool>,
+    #[account(
+        mut,
+        seeds = [STAKE_SEED, pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner
+    )]
+    pub position: Account<'info, StakePosition>,
+    #[account(
+        mut,
+        seeds = [STAKE_VAULT_SEED, pool.key().as_ref()],
+        bump = pool.stake_vault_bump
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [REWARD_VAULT_SEED, pool.key().as_ref()],
+        bump = pool.reward_vault_bump
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner,
+        token::token_program = token_program
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    pub owner: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, mint.key().as_ref(), pool.admin.as_ref()],
+        bump = pool.bump,
+        has_one = mint
+    )]
+    pub pool: Account<'info, StakingPool>,
+    #[account(
+        mut,
+        seeds = [STAKE_SEED, pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner
+    )]
+    pub position: Account<'info, StakePosition>,
+    #[account(
+        mut,
+        seeds = [REWARD_VAULT_SEED, pool.key().as_ref()],
+        bump = pool.reward_vault_bump
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner,
+        token::token_program = token_program
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetRewardRate<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [POOL_SEED, pool.mint.as_ref(), pool.admin.as_ref()],
+        bump = pool.bump,
+        has_one = admin
+    )]
+    pub pool: Account<'info, StakingPool>,
+    #[account(
+        seeds = [REWARD_VAULT_SEED, pool.key().as_ref()],
+        bump = pool.reward_vault_bump
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+}
+
+/// Rewards due at the current rate since `last_update_ts`, capped at what
+/// the reward vault holds beyond rewards already accrued to stakers. Time
+/// with nothing staked accrues nothing.
+fn accrued_rewards(pool: &StakingPool, reward_vault_balance: u64, now: i64) -> Result<u64> {
+    if pool.total_staked == 0 {
+        return Ok(0);
+    }
+    let elapsed = now.saturating_sub(pool.last_update_ts).max(0) as u128;
+    let due = elapsed
+        .checked_mul(pool.reward_rate as u128)
+        .ok_or(StakingError::MathOverflow)?;
+    let funded = reward_vault_balance.saturating_sub(pool.reserved_rewards);
+    Ok(due.min(funded as u128) as u64)
+}
+
+/// Folds everything accrued at the current rate into the accumulator. Must
+/// run before `reward_rate` or `total_staked` changes. Time the reward vault
+/// could not cover is not paid out later.
+fn update_pool(pool: &mut StakingPool, reward_vault_balance: u64, now: i64) -> Result<()> {
+    let accrued = accrued_rewards(pool, reward_vault_balance, now)?;
+    if accrued > 0 {
+        let per_token = (accrued as u128)
+            .checked_mul(REWARD_PRECISION)
+            .ok_or(StakingError::MathOverflow)?
+            .checked_div(pool.total_staked as u128)
+            .ok_or(StakingError::MathOverflow)?;
+        pool.reward_per_token_stored = pool
+            .reward_per_token_stored
+            .checked_add(per_token)
+            .ok_or(StakingError::MathOverflow)?;
+        pool.reserved_rewards = pool
+            .reserved_rewards
+            .checked_add(accrued)
+            .ok_or(StakingError::MathOverflow)?;
+    }
+    pool.last_update_ts = now;
+    Ok(())
+}
+
+/// Moves a position's share of the accumulator into `rewards_owed`. Call
+/// after `update_pool` and before the position's amount changes.
+fn settle_position(pool: &StakingPool, position: &mut StakePosition) -> Result<()> {
+    let delta = pool
+        .reward_per_token_stored
+        .checked_sub(position.reward_per_token_paid)
+        .ok_or(StakingError::MathOverflow)?;
+    let earned = (position.amount as u128)
+        .checked_mul(delta)
+        .ok_or(StakingError::MathOverflow)?
+        .checked_div(REWARD_PRECISION)
+        .ok_or(StakingError::MathOverflow)?;
+    position.rewards_owed = position
+        .rewards_owed
+        .checked_add(u64::try_from(earned).map_err(|_| StakingError::MathOverflow)?)
+        .ok_or(StakingError::MathOverflow)?;
+    position.reward_per_token_paid = pool.reward_per_token_stored;
+    Ok(())
+}
+
+/// Moves tokens out of one of the pool's vaults. Goes through the Token-2022
+/// helper so transfer hook accounts passed in `remaining_accounts` are
+/// forwarded.
+fn transfer_from_pool<'info>(
+    pool: &Account<'info, StakingPool>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    destination: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[POOL_SEED, pool.mint.as_ref(), pool.admin.as_ref(), &[pool.bump]];
+    invoke_transfer_checked(
+        token_program.key,
+        vault.to_account_info(),
+        mint.to_account_info(),
+        destination,
+        pool.to_account_info(),
+        remaining_accounts,
+        amount,
+        mint.decimals,
+        &[&seeds[..]],
+    )?;
+    Ok(())
+}
+
+#[program]
+pub mod tdl_staking {
+    use super::*;
+
+    /// Creates the pool and its stake and reward vaults. The reward vault is
+    /// meant to be the destination of the `dollar_token` Rewards bucket.
+    pub fn initialize_pool(
+        ctx: Context<InitializePool>,
+        reward_rate: u64,
+        lockup_seconds: i64,
+    ) -> Result<()> {
+        require!(lockup_seconds >= 0, StakingError::InvalidLockup);
+
+        let pool = &mut ctx.accounts.pool;
+        pool.admin = ctx.accounts.admin.key();
+        pool.mint = ctx.accounts.mint.key();
+        pool.stake_vault = ctx.accounts.stake_vault.key();
+        pool.reward_vault = ctx.accounts.reward_vault.key();
+        pool.reward_rate = reward_rate;
+        pool.lockup_seconds = lockup_seconds;
+        pool.total_staked = 0;
+        pool.reward_per_token_stored = 0;
+        pool.reserved_rewards = 0;
+        pool.last_update_ts = Clock::get()?.unix_timestamp;
+        pool.bump = ctx.bumps.pool;
+        pool.stake_vault_bump = ctx.bumps.stake_vault;
+        pool.reward_vault_bump = ctx.bumps.reward_vault;
+
+        emit!(PoolInitialized {
+            pool: pool.key(),
+            admin: pool.admin,
+            mint: pool.mint,
+            reward_vault: pool.reward_vault,
+            reward_rate,
+            lockup_seconds,
+        });
+
+        Ok(())
+    }
+
+    /// Deposits `amount` into the stake vault and credits what the vault
+    /// actually received, which is less when the mint withholds a transfer
+    /// fee. Every deposit restarts the position's lockup.
+    pub fn stake<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Stake<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, StakingError::InvalidAmount);
+        let now = Clock::get()?.unix_timestamp;
+
+        let pool = &mut ctx.accounts.pool;
+        update_pool(pool, ctx.accounts.reward_vault.amount, now)?;
+
+        let position = &mut ctx.accounts.position;
+        if position.is_uninitialized() {
+            position.owner = ctx.accounts.owner.key();
+            position.pool = pool.key();
+            position.bump = ctx.bumps.position;
+            position.reward_per_token_paid = pool.reward_per_token_stored;
+        }
+        settle_position(pool, position)?;
+
+        let vault_before = ctx.accounts.stake_vault.amount;
+        invoke_transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.owner_token_account.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.stake_vault.to_account_info(),
+            ctx.accounts.owner.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            ctx.accounts.mint.decimals,
+            &[],
+        )?;
+        ctx.accounts.stake_vault.reload()?;
+        let amount = ctx
+            .accounts
+            .stake_vault
+            .amount
+            .checked_sub(vault_before)
+            .ok_or(StakingError::MathOverflow)?;
+        require!(amount > 0, StakingError::InvalidAmount);
+
+        position.amount = position
+            .amount
+            .checked_add(amount)
+            .ok_or(StakingError::MathOverflow)?;
+        position.locked_until = now
+            .checked_add(pool.lockup_seconds)
+            .ok_or(StakingError::MathOverflow)?;
+        pool.total_staked = pool
+            .total_staked
+            .checked_add(amount)
+            .ok_or(StakingError::MathOverflow)?;
+
+        emit!(Staked {
+            pool: pool.key(),
+            owner: position.owner,
+            amount,
+            total_staked: pool.total_staked,
+            locked_until: position.locked_until,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraws `amount` of principal once the lockup has passed. Rewards
+    /// earned so far stay on the position for `claim`.
+    pub fn unstake<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Unstake<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, StakingError::InvalidAmount);
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now >= ctx.accounts.position.locked_until,
+            StakingError::StillLocked
+        );
+        require!(
+            amount <= ctx.accounts.position.amount,
+            StakingError::InsufficientStake
+        );
+
+        update_pool(&mut ctx.accounts.pool, ctx.accounts.reward_vault.amount, now)?;
+        settle_position(&ctx.accounts.pool, &mut ctx.accounts.position)?;
+
+        transfer_from_pool(
+            &ctx.accounts.pool,
+            &ctx.accounts.stake_vault,
+            &ctx.accounts.mint,
+            ctx.accounts.owner_token_account.to_account_info(),
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+            amount,
+        )?;
+
+        let position = &mut ctx.accounts.position;
+        position.amount -= amount;
+        let pool = &mut ctx.accounts.pool;
+        pool.total_staked = pool
+            .total_staked
+            .checked_sub(amount)
+            .ok_or(StakingError::MathOverflow)?;
+
+        emit!(Unstaked {
+            pool: pool.key(),
+            owner: position.owner,
+            amount,
+            total_staked: pool.total_staked,
+        });
+
+        Ok(())
+    }
+
+    /// Pays out everything the position has earned from the reward vault.
+    pub fn claim<'info>(ctx: Context<'_, '_, 'info, 'info, Claim<'info>>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        update_pool(&mut ctx.accounts.pool, ctx.accounts.reward_vault.amount, now)?;
+        settle_position(&ctx.accounts.pool, &mut ctx.accounts.position)?;
+
+        let amount = ctx.accounts.position.rewards_owed;
+        require!(amount > 0, StakingError::NothingToClaim);
+        require!(
+            ctx.accounts.reward_vault.amount >= amount,
+            StakingError::InsufficientRewards
+        );
+
+        transfer_from_pool(
+            &ctx.accounts.pool,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.mint,
+            ctx.accounts.owner_token_account.to_account_info(),
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+            amount,
+        )?;
+
+        let position = &mut ctx.accounts.position;
+        position.rewards_owed = 0;
+        let pool = &mut ctx.accounts.pool;
+        pool.reserved_rewards = pool
+            .reserved_rewards
+            .checked_sub(amount)
+            .ok_or(StakingError::MathOverflow)?;
+
+        emit!(RewardsClaimed {
+            pool: ctx.accounts.pool.key(),
+            owner: position.owner,
+            amount,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Changes the per-second reward rate. Rewards up to now are settled at
+    /// the old rate first, so a cut never touches what was already earned.
+    pub fn set_reward_rate(ctx: Context<SetRewardRate>, reward_rate: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let pool = &mut ctx.accounts.pool;
+        update_pool(pool, ctx.accounts.reward_vault.amount, now)?;
+
+        let old_rate = pool.reward_rate;
+        pool.reward_rate = reward_rate;
+
+        emit!(RewardRateUpdated {
+            pool: pool.key(),
+            old_rate,
+            new_rate: reward_rate,
+            reward_per_token: pool.reward_per_token_stored,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct StakingPool {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub stake_vault: Pubkey,
+    pub reward_vault: Pubkey,
+    /// Tokens paid out per second across all stakers
+    pub reward_rate: u64,
+    pub lockup_seconds: i64,
+    pub total_staked: u64,
+    /// Rewards per staked token, scaled by `REWARD_PRECISION`
+    pub reward_per_token_stored: u128,
+    /// Accrued to stakers but not yet claimed; accrual never outgrows the
+    /// reward vault balance
+    pub reserved_rewards: u64,
+    pub last_update_ts: i64,
+    pub bump: u8,
+    pub stake_vault_bump: u8,
+    pub reward_vault_bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct StakePosition {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub reward_per_token_paid: u128,
+    pub rewards_owed: u64,
+    pub locked_until: i64,
+    pub bump: u8,
+}
+
+impl StakePosition {
+    pub fn is_uninitialized(&self) -> bool {
+        self.owner == Pubkey::default()
+    }
+}
+
+#[event]
+pub struct PoolInitialized {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub reward_vault: Pubkey,
+    pub reward_rate: u64,
+    pub lockup_seconds: i64,
+}
+
+#[event]
+pub struct Staked {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub total_staked: u64,
+    pub locked_until: i64,
+}
+
+#[event]
+pub struct Unstaked {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct RewardsClaimed {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardRateUpdated {
+    pub pool: Pubkey,
+    pub old_rate: u64,
+    pub new_rate: u64,
+    pub reward_per_token: u128,
+    pub timestamp: i64,
+}
+
+#[error_code]
+pub enum StakingError {
+    #[msg("Invalid amount provided")]
+    InvalidAmount,
+    #[msg("Invalid lockup period")]
+    InvalidLockup,
+    #[msg("Math overflow occurred")]
+    MathOverflow,
+    #[msg("Unstake amount exceeds staked balance")]
+    InsufficientStake,
+    #[msg("Stake is still locked")]
+    StillLocked,
+    #[msg("Nothing to claim")]
+    NothingToClaim,
+    #[msg("Reward vault cannot cover the claim")]
+    InsufficientRewards,
+}
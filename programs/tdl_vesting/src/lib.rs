@@ -0,0 +1,373 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    spl_token_2022::onchain::invoke_transfer_checked, Mint, TokenAccount, TokenInterface,
+};
+
+declare_id!("7szViJxXGNfgT3usxYJ3UqT5gfEAERRpCUFVEq9qBTXt");
+
+const GRANT_SEED: &[u8] = b"grant";
+const VAULT_SEED: &[u8] = b"vault";
+
+#[derive(Accounts)]
+pub struct CreateGrant<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: Only recorded on the grant
+    pub beneficiary: UncheckedAccount<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + VestingGrant::INIT_SPACE,
+        seeds = [GRANT_SEED, mint.key().as_ref(), admin.key().as_ref(), beneficiary.key().as_ref()],
+        bump
+    )]
+    pub grant: Account<'info, VestingGrant>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [VAULT_SEED, grant.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = grant,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = admin,
+        token::token_program = token_program
+    )]
+    pub admin_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Release<'info> {
+    pub beneficiary: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [GRANT_SEED, mint.key().as_ref(), grant.admin.as_ref(), beneficiary.key().as_ref()],
+        bump = grant.bump,
+        has_one = beneficiary,
+        has_one = mint
+    )]
+    pub grant: Account<'info, VestingGrant>,
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, grant.key().as_ref()],
+        bump = grant.vault_bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = beneficiary,
+        token::token_program = token_program
+    )]
+    pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct Revoke<'info> {
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [GRANT_SEED, mint.key().as_ref(), admin.key().as_ref(), grant.beneficiary.as_ref()],
+        bump = grant.bump,
+        has_one = admin,
+        has_one = mint
+    )]
+    pub grant: Account<'info, VestingGrant>,
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, grant.key().as_ref()],
+        bump = grant.vault_bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub refund_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+fn validate_schedule(schedule: &VestingSchedule) -> Result<()> {
+    require!(schedule.duration_seconds > 0, VestingError::InvalidSchedule);
+    require!(
+        schedule.cliff_seconds >= 0 && schedule.cliff_seconds <= schedule.duration_seconds,
+        VestingError::InvalidSchedule
+    );
+    require!(
+        schedule.period_seconds >= 0 && schedule.period_seconds <= schedule.duration_seconds,
+        VestingError::InvalidSchedule
+    );
+    Ok(())
+}
+
+/// Same linear unlock as `tdl_presale::calculate_claimable`, measured from
+/// `start_ts`. Nothing vests before the cliff; with a period set, elapsed
+/// time is rounded down to whole periods.
+fn calculate_vested(total: u64, schedule: &VestingSchedule, now: i64) -> Result<u64> {
+    let elapsed = now.saturating_sub(schedule.start_ts);
+    if elapsed < schedule.cliff_seconds {
+        return Ok(0);
+    }
+    if elapsed >= schedule.duration_seconds {
+        return Ok(total);
+    }
+
+    let elapsed = if schedule.period_seconds > 0 {
+        elapsed - elapsed % schedule.period_seconds
+    } else {
+        elapsed
+    };
+    let vested = (total as u128)
+        .checked_mul(elapsed as u128)
+        .ok_or(VestingError::MathOverflow)?
+        .checked_div(schedule.duration_seconds as u128)
+        .ok_or(VestingError::MathOverflow)?;
+    Ok(vested as u64)
+}
+
+/// Moves tokens out of a grant's vault. Goes through the Token-2022 helper so
+/// transfer hook accounts passed in `remaining_accounts` are forwarded.
+fn transfer_from_vault<'info>(
+    grant: &Account<'info, VestingGrant>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    destination: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[
+        GRANT_SEED,
+        grant.mint.as_ref(),
+        grant.admin.as_ref(),
+        grant.beneficiary.as_ref(),
+        &[grant.bump],
+    ];
+    invoke_transfer_checked(
+        token_program.key,
+        vault.to_account_info(),
+        mint.to_account_info(),
+        destination,
+        grant.to_account_info(),
+        remaining_accounts,
+        amount,
+        mint.decimals,
+        &[&seeds[..]],
+    )?;
+    Ok(())
+}
+
+#[program]
+pub mod tdl_vesting {
+    use super::*;
+
+    /// Locks `total` tokens from the admin into a vault owned by the grant.
+    /// The grant vests what actually reached the vault, which is less than
+    /// `total` when the mint withholds a transfer fee.
+    pub fn create_grant<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateGrant<'info>>,
+        total: u64,
+        schedule: VestingSchedule,
+        revocable: bool,
+    ) -> Result<()> {
+        require!(total > 0, VestingError::InvalidAmount);
+        validate_schedule(&schedule)?;
+
+        invoke_transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.admin_token_account.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.admin.to_account_info(),
+            ctx.remaining_accounts,
+            total,
+            ctx.accounts.mint.decimals,
+            &[],
+        )?;
+        ctx.accounts.vault.reload()?;
+        let total = ctx.accounts.vault.amount;
+        require!(total > 0, VestingError::InvalidAmount);
+
+        let grant = &mut ctx.accounts.grant;
+        grant.admin = ctx.accounts.admin.key();
+        grant.beneficiary = ctx.accounts.beneficiary.key();
+        grant.mint = ctx.accounts.mint.key();
+        grant.total = total;
+        grant.released = 0;
+        grant.schedule = schedule;
+        grant.revocable = revocable;
+        grant.revoked_at = None;
+        grant.bump = ctx.bumps.grant;
+        grant.vault_bump = ctx.bumps.vault;
+
+        emit!(GrantCreated {
+            grant: grant.key(),
+            admin: grant.admin,
+            beneficiary: grant.beneficiary,
+            total,
+            schedule,
+            revocable,
+        });
+
+        Ok(())
+    }
+
+    /// Sends everything vested so far to the beneficiary. A revoked grant
+    /// stops vesting at the moment of revocation.
+    pub fn release<'info>(ctx: Context<'_, '_, 'info, 'info, Release<'info>>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let grant = &ctx.accounts.grant;
+        let vested_at = grant.revoked_at.unwrap_or(now).min(now);
+        let vested = calculate_vested(grant.total, &grant.schedule, vested_at)?;
+        let releasable = vested
+            .checked_sub(grant.released)
+            .ok_or(VestingError::MathOverflow)?;
+        require!(releasable > 0, VestingError::NothingToRelease);
+
+        transfer_from_vault(
+            grant,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            ctx.accounts.beneficiary_token_account.to_account_info(),
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+            releasable,
+        )?;
+
+        let grant = &mut ctx.accounts.grant;
+        grant.released = grant
+            .released
+            .checked_add(releasable)
+            .ok_or(VestingError::MathOverflow)?;
+
+        emit!(TokensReleased {
+            grant: grant.key(),
+            beneficiary: grant.beneficiary,
+            amount: releasable,
+            released: grant.released,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the unvested remainder to `refund_account`. Tokens vested
+    /// before revocation stay in the vault for the beneficiary to release.
+    pub fn revoke<'info>(ctx: Context<'_, '_, 'info, 'info, Revoke<'info>>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let grant = &ctx.accounts.grant;
+        require!(grant.revocable, VestingError::NotRevocable);
+        require!(grant.revoked_at.is_none(), VestingError::AlreadyRevoked);
+
+        let vested = calculate_vested(grant.total, &grant.schedule, now)?;
+        let unvested = grant
+            .total
+            .checked_sub(vested)
+            .ok_or(VestingError::MathOverflow)?;
+        if unvested > 0 {
+            transfer_from_vault(
+                grant,
+                &ctx.accounts.vault,
+                &ctx.accounts.mint,
+                ctx.accounts.refund_account.to_account_info(),
+                &ctx.accounts.token_program,
+                ctx.remaining_accounts,
+                unvested,
+            )?;
+        }
+
+        let grant = &mut ctx.accounts.grant;
+        grant.revoked_at = Some(now);
+
+        emit!(GrantRevoked {
+            grant: grant.key(),
+            beneficiary: grant.beneficiary,
+            vested,
+            refunded: unvested,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct VestingGrant {
+    pub admin: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub total: u64,
+    pub released: u64,
+    pub schedule: VestingSchedule,
+    pub revocable: bool,
+    pub revoked_at: Option<i64>,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+/// `period_seconds == 0` unlocks linearly every second; otherwise tokens
+/// unlock in steps of one period. The cliff is measured from `start_ts`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct VestingSchedule {
+    pub start_ts: i64,
+    pub cliff_seconds: i64,
+    pub duration_seconds: i64,
+    pub period_seconds: i64,
+}
+
+#[event]
+pub struct GrantCreated {
+    pub grant: Pubkey,
+    pub admin: Pubkey,
+    pub beneficiary: Pubkey,
+    pub total: u64,
+    pub schedule: VestingSchedule,
+    pub revocable: bool,
+}
+
+#[event]
+pub struct TokensReleased {
+    pub grant: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub released: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GrantRevoked {
+    pub grant: Pubkey,
+    pub beneficiary: Pubkey,
+    pub vested: u64,
+    pub refunded: u64,
+    pub timestamp: i64,
+}
+
+#[error_code]
+pub enum VestingError {
+    #[msg("Invalid amount provided")]
+    InvalidAmount,
+    #[msg("Invalid vesting schedule")]
+    InvalidSchedule,
+    #[msg("Math overflow occurred")]
+    MathOverflow,
+    #[msg("Nothing to release")]
+    NothingToRelease,
+    #[msg("Grant is not revocable")]
+    NotRevocable,
+    #[msg("Grant already revoked")]
+    AlreadyRevoked,
+}
@@ -0,0 +1,94 @@
+import { keccak_256 } from "@noble/hashes/sha3";
+import { PublicKey } from "@solana/web3.js";
+
+// Sentinels bracket every unlisted wallet with two neighbouring leaves
+const LOWEST = new PublicKey(new Uint8Array(32));
+const HIGHEST = new PublicKey(new Uint8Array(32).fill(0xff));
+
+export type ExclusionProof = {
+  index: number;
+  lower: PublicKey;
+  lowerProof: number[][];
+  upper: PublicKey;
+  upperProof: number[][];
+};
+
+export type BlacklistArtifacts = {
+  root: Uint8Array;
+  leaves: PublicKey[];
+  // Returns null when the wallet is on the list
+  exclusionProof: (wallet: PublicKey) => ExclusionProof | null;
+};
+
+/**
+ * Builds the sorted, positional tree used by `set_blacklist_root`. Unlike
+ * the whitelist tree, siblings are hashed in leaf order so two proofs can
+ * show that their leaves are adjacent.
+ */
+export function computeBlacklistArtifacts(addresses: PublicKey[]): BlacklistArtifacts {
+  const leaves = [LOWEST, ...addresses, HIGHEST]
+    .sort((a, b) => Buffer.compare(a.toBuffer(), b.toBuffer()))
+    .filter((key, idx, all) => idx === 0 || !key.equals(all[idx - 1]));
+  const tree = buildTree(leaves.map(hashLeaf));
+  const root = tree[tree.length - 1][0];
+
+  const exclusionProof = (wallet: PublicKey): ExclusionProof | null => {
+    const upperIndex = leaves.findIndex(
+      (leaf) => Buffer.compare(leaf.toBuffer(), wallet.toBuffer()) >= 0
+    );
+    if (upperIndex <= 0 || leaves[upperIndex].equals(wallet)) {
+      return null;
+    }
+    const index = upperIndex - 1;
+    return {
+      index,
+      lower: leaves[index],
+      lowerProof: buildProof(tree, index),
+      upper: leaves[upperIndex],
+      upperProof: buildProof(tree, upperIndex),
+    };
+  };
+
+  return { root, leaves, exclusionProof };
+}
+
+function hashLeaf(key: PublicKey): Uint8Array {
+  return keccak_256(Buffer.concat([Buffer.from([0]), key.toBuffer()]));
+}
+
+function hashNode(left: Uint8Array, right: Uint8Array): Uint8Array {
+  return keccak_256(Buffer.concat([Buffer.from([1]), Buffer.from(left), Buffer.from(right)]));
+}
+
+function buildTree(level: Uint8Array[]): Uint8Array[][] {
+  const tree: Uint8Array[][] = [level];
+  let current = level;
+
+  while (current.length > 1) {
+    const next: Uint8Array[] = [];
+    for (let i = 0; i < current.length; i += 2) {
+      const left = current[i];
+      const right = i + 1 < current.length ? current[i + 1] : current[i];
+      next.push(hashNode(left, right));
+    }
+    tree.push(next);
+    current = next;
+  }
+
+  return tree;
+}
+
+function buildProof(tree: Uint8Array[][], index: number): number[][] {
+  const proof: number[][] = [];
+  let idx = index;
+
+  for (let level = 0; level < tree.length - 1; level++) {
+    const nodes = tree[level];
+    const pairIndex = idx % 2 === 1 ? idx - 1 : idx + 1;
+    // Odd levels duplicate their last node
+    proof.push(Array.from(pairIndex < nodes.length ? nodes[pairIndex] : nodes[idx]));
+    idx = Math.floor(idx / 2);
+  }
+
+  return proof;
+}
@@ -131,7 +131,7 @@ blacklist
         wallets.map((w: string) => new PublicKey(w))
       );
       
-      console.log(`✅ Completed: ${results.length}/${wallets.length} successful`);
+      console.log(`✅ Completed in ${results.length} transactions`);
       
     } catch (error) {
       console.error('❌ Error:', error.message);
@@ -259,7 +259,7 @@ whitelist
         }))
       );
       
-      console.log(`✅ Completed: ${results.length}/${wallets.length} successful`);
+      console.log(`✅ Completed in ${results.length} transactions`);
       
     } catch (error) {
       console.error('❌ Error:', error.message);
@@ -180,6 +180,22 @@ class TokenMonitor {
       });
     }
 
+    // Every treasury drain step is worth a look
+    const recentDrains = this.eventLog.filter(
+      e => e.type.startsWith('drain_') && (now - e.timestamp) < timeWindow
+    );
+    recentDrains.forEach(e => {
+      alerts.push({ severity: 'high', message: e.description });
+    });
+
+    // A freeze means someone is responding to an incident
+    const recentFreezes = this.eventLog.filter(
+      e => e.type === 'account_frozen' && (now - e.timestamp) < timeWindow
+    );
+    recentFreezes.forEach(e => {
+      alerts.push({ severity: 'high', message: e.description });
+    });
+
     return alerts;
   }
 
@@ -220,11 +236,63 @@ class TokenMonitor {
       });
     });
 
+    const listener4 = program.addEventListener("ActionQueued", (event, slot) => {
+      if (!event.action.drainLiquidity) return;
+      const eta = new Date(event.eta.toNumber() * 1000).toLocaleString();
+      this.logEvent({
+        type: "drain_announced",
+        description: `Drain of ${event.action.drainLiquidity.amount.toString()} announced, executable ${eta}`,
+        timestamp: Date.now(),
+        data: event,
+      });
+    });
+
+    const listener5 = program.addEventListener("LiquidityDrained", (event, slot) => {
+      this.logEvent({
+        type: "drain_executed",
+        description: `Drained ${event.amount.toString()} from ${event.from.toString().slice(0, 8)}... (${event.epochOutflow.toString()} this epoch)`,
+        timestamp: Date.now(),
+        data: event,
+      });
+    });
+
+    const listener6 = program.addEventListener("TreasuryPoolRegistered", (event, slot) => {
+      this.logEvent({
+        type: "drain_pool_registered",
+        description: `Pool ${event.pool.toString().slice(0, 8)}... registered with epoch cap ${event.epochCap.toString()}`,
+        timestamp: Date.now(),
+        data: event,
+      });
+    });
+
+    const listener7 = program.addEventListener("AccountFrozen", (event, slot) => {
+      this.logEvent({
+        type: "account_frozen",
+        description: `Account ${event.account.toString().slice(0, 8)}... frozen (reason ${event.reasonCode}, ${event.frozenAccounts.toString()} frozen)`,
+        timestamp: Date.now(),
+        data: event,
+      });
+    });
+
+    const listener8 = program.addEventListener("AccountThawed", (event, slot) => {
+      this.logEvent({
+        type: "account_thawed",
+        description: `Account ${event.account.toString().slice(0, 8)}... thawed (reason ${event.reasonCode}, ${event.frozenAccounts.toString()} frozen)`,
+        timestamp: Date.now(),
+        data: event,
+      });
+    });
+
     // Clean up on exit
     process.on("SIGINT", () => {
       program.removeEventListener(listener);
       program.removeEventListener(listener2);
       program.removeEventListener(listener3);
+      program.removeEventListener(listener4);
+      program.removeEventListener(listener5);
+      program.removeEventListener(listener6);
+      program.removeEventListener(listener7);
+      program.removeEventListener(listener8);
       console.log("\n\n👋 Monitoring stopped. Goodbye!");
       process.exit(0);
     });
@@ -6,8 +6,71 @@ import {
   TOKEN_PROGRAM_ID,
   getAssociatedTokenAddress,
   createAssociatedTokenAccountInstruction,
+  getAccount,
 } from "@solana/spl-token";
 import { assert } from "chai";
+import { computeMerkleArtifacts } from "../scripts/whitelist-merkle";
+import { computeBlacklistArtifacts } from "../scripts/blacklist-merkle";
+
+// 100,000,000 TDL with 9 decimals
+const MAX_SUPPLY = new anchor.BN("100000000000000000");
+
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
+const metadataPda = (mint: PublicKey) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+
+// Role discriminants double as the role byte in grant PDA seeds
+const Role = {
+  MINTER: 0,
+  BURNER: 1,
+  COMPLIANCE_OFFICER: 2,
+  PAUSER: 3,
+  TREASURER: 4,
+  ROLE_ADMIN: 5,
+};
+const ROLE_ARGS = [
+  { minter: {} },
+  { burner: {} },
+  { complianceOfficer: {} },
+  { pauser: {} },
+  { treasurer: {} },
+  { roleAdmin: {} },
+];
+
+// Bucket discriminants double as the category byte in bucket PDA seeds
+const Bucket = {
+  PRESALE: 0,
+  LIQUIDITY: 1,
+  REWARDS: 2,
+  TEAM: 3,
+  MARKETING: 4,
+};
+const BUCKET_ARGS = [
+  { presale: {} },
+  { liquidity: {} },
+  { rewards: {} },
+  { team: {} },
+  { marketing: {} },
+];
+
+// Reason codes are defined by the compliance team; 1 = suspected bot
+const REASON_BOT = 1;
+const NO_REFERENCE = Array(32).fill(0);
+
+// controlled_transfer arguments while no Merkle roots are set
+const NO_PROOFS = {
+  senderWhitelist: [],
+  recipientWhitelist: [],
+  senderExclusion: null,
+  recipientExclusion: null,
+};
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
 
 describe("Access Control Features", () => {
   const provider = anchor.AnchorProvider.env();
@@ -18,14 +81,104 @@ describe("Access Control Features", () => {
   const mintKeypair = Keypair.generate();
   
   let tokenInfoPda: PublicKey;
+  let mintAuthorityPda: PublicKey;
   let authorityTokenAccount: PublicKey;
 
+  const rolePda = (role: number, holder: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("role"),
+        mintKeypair.publicKey.toBuffer(),
+        Buffer.from([role]),
+        holder.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  const whitelistPda = (wallet: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("whitelist"), mintKeypair.publicKey.toBuffer(), wallet.toBuffer()],
+      program.programId
+    )[0];
+
+  const limitExemptionPda = (account: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("limit_exemption"), mintKeypair.publicKey.toBuffer(), account.toBuffer()],
+      program.programId
+    )[0];
+
+  const feeExemptionPda = (account: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("fee_exemption"), mintKeypair.publicKey.toBuffer(), account.toBuffer()],
+      program.programId
+    )[0];
+
+  const velocityPda = (wallet: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("velocity"), mintKeypair.publicKey.toBuffer(), wallet.toBuffer()],
+      program.programId
+    )[0];
+
+  const bucketPda = (category: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("bucket"), mintKeypair.publicKey.toBuffer(), Buffer.from([category])],
+      program.programId
+    )[0];
+
+  // Creates a bucket paying into `destination` and releases `released` of it
+  const openBucket = async (
+    category: number,
+    cap: anchor.BN,
+    destination: PublicKey,
+    released: anchor.BN
+  ) => {
+    await program.methods
+      .createAllocationBucket(BUCKET_ARGS[category] as any, cap, destination)
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        roleGrant: rolePda(Role.MINTER, authority.publicKey),
+        bucket: bucketPda(category),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .releaseAllocationBucket(released)
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        roleGrant: rolePda(Role.MINTER, authority.publicKey),
+        bucket: bucketPda(category),
+      })
+      .rpc();
+  };
+
+  const grantRole = (role: number, holder: PublicKey) =>
+    program.methods
+      .grantRole(ROLE_ARGS[role] as any, holder, null)
+      .accounts({
+        admin: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        adminGrant: null,
+        roleGrant: rolePda(role, holder),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
   before(async () => {
     [tokenInfoPda] = await PublicKey.findProgramAddress(
       [Buffer.from("token_info"), mintKeypair.publicKey.toBuffer()],
       program.programId
     );
 
+    [mintAuthorityPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("mint_authority"), mintKeypair.publicKey.toBuffer()],
+      program.programId
+    );
+
     authorityTokenAccount = await getAssociatedTokenAddress(
       mintKeypair.publicKey,
       authority.publicKey
@@ -33,18 +186,26 @@ describe("Access Control Features", () => {
 
     // Initialize token
     await program.methods
-      .initializeToken("Dollar Token", "TDL", "https://example.com/metadata.json", 9)
+      .initializeToken("Dollar Token", "TDL", "https://example.com/metadata.json", 9, MAX_SUPPLY)
       .accounts({
         authority: authority.publicKey,
         mint: mintKeypair.publicKey,
+        mintAuthority: mintAuthorityPda,
         tokenInfo: tokenInfoPda,
+        metadata: metadataPda(mintKeypair.publicKey),
         tokenProgram: TOKEN_PROGRAM_ID,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
       })
       .signers([mintKeypair])
       .rpc();
 
+    await grantRole(Role.MINTER, authority.publicKey);
+    await grantRole(Role.COMPLIANCE_OFFICER, authority.publicKey);
+    await grantRole(Role.PAUSER, authority.publicKey);
+    await grantRole(Role.TREASURER, authority.publicKey);
+
     // Create token account
     const createAtaIx = createAssociatedTokenAccountInstruction(
       authority.publicKey,
@@ -55,14 +216,25 @@ describe("Access Control Features", () => {
     const tx = new anchor.web3.Transaction().add(createAtaIx);
     await provider.sendAndConfirm(tx);
 
+    await openBucket(
+      Bucket.PRESALE,
+      MAX_SUPPLY.divn(4),
+      authorityTokenAccount,
+      new anchor.BN(1_000_000_000_000)
+    );
+
     // Mint some tokens
     await program.methods
       .mintTokens(new anchor.BN(1_000_000_000_000))
       .accounts({
         authority: authority.publicKey,
         mint: mintKeypair.publicKey,
+        mintAuthority: mintAuthorityPda,
         tokenInfo: tokenInfoPda,
+        roleGrant: rolePda(Role.MINTER, authority.publicKey),
+        bucket: bucketPda(Bucket.PRESALE),
         destination: authorityTokenAccount,
+        destinationWhitelist: whitelistPda(authority.publicKey),
         tokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
@@ -81,11 +253,12 @@ describe("Access Control Features", () => {
 
     it("Should add wallet to blacklist", async () => {
       const tx = await program.methods
-        .addToBlacklist(maliciousWallet)
+        .addToBlacklist(maliciousWallet, REASON_BOT, "Sniper bot at launch", null, NO_REFERENCE)
         .accounts({
           authority: authority.publicKey,
           mint: mintKeypair.publicKey,
           tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
           blacklist: blacklistPda,
           systemProgram: SystemProgram.programId,
         })
@@ -96,6 +269,9 @@ describe("Access Control Features", () => {
       const blacklistAccount = await program.account.blacklistEntry.fetch(blacklistPda);
       assert.equal(blacklistAccount.wallet.toString(), maliciousWallet.toString());
       assert.isTrue(blacklistAccount.isBlacklisted);
+      assert.equal(blacklistAccount.reasonCode, REASON_BOT);
+      assert.equal(blacklistAccount.reason, "Sniper bot at launch");
+      assert.isNull(blacklistAccount.expiresAt);
     });
 
     it("Should remove wallet from blacklist", async () => {
@@ -105,6 +281,7 @@ describe("Access Control Features", () => {
           authority: authority.publicKey,
           mint: mintKeypair.publicKey,
           tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
           blacklist: blacklistPda,
         })
         .rpc();
@@ -123,6 +300,7 @@ describe("Access Control Features", () => {
           authority: authority.publicKey,
           mint: mintKeypair.publicKey,
           tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
         })
         .rpc();
 
@@ -136,6 +314,7 @@ describe("Access Control Features", () => {
           authority: authority.publicKey,
           mint: mintKeypair.publicKey,
           tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
         })
         .rpc();
 
@@ -148,11 +327,12 @@ describe("Access Control Features", () => {
       
       try {
         await program.methods
-          .addToBlacklist(maliciousWallet)
+          .addToBlacklist(maliciousWallet, REASON_BOT, "", null, NO_REFERENCE)
           .accounts({
             authority: unauthorized.publicKey,
             mint: mintKeypair.publicKey,
             tokenInfo: tokenInfoPda,
+            roleGrant: rolePda(Role.COMPLIANCE_OFFICER, unauthorized.publicKey),
             blacklist: blacklistPda,
             systemProgram: SystemProgram.programId,
           })
@@ -161,9 +341,29 @@ describe("Access Control Features", () => {
         
         assert.fail("Expected transaction to fail");
       } catch (error) {
-        assert.include(error.toString(), "UnauthorizedAccess");
+        assert.include(error.toString(), "AccountNotInitialized");
       }
     });
+
+    it("Should close an entry and return its rent", async () => {
+      const receiver = Keypair.generate().publicKey;
+      const rent = await provider.connection.getBalance(blacklistPda);
+
+      await program.methods
+        .closeBlacklistEntry(maliciousWallet)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+          blacklist: blacklistPda,
+          rentReceiver: receiver,
+        })
+        .rpc();
+
+      assert.isNull(await provider.connection.getAccountInfo(blacklistPda));
+      assert.equal(await provider.connection.getBalance(receiver), rent);
+    });
   });
 
   describe("Whitelist Functionality", () => {
@@ -185,6 +385,7 @@ describe("Access Control Features", () => {
           authority: authority.publicKey,
           mint: mintKeypair.publicKey,
           tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
           whitelist: whitelistPda,
           systemProgram: SystemProgram.programId,
         })
@@ -205,6 +406,7 @@ describe("Access Control Features", () => {
           authority: authority.publicKey,
           mint: mintKeypair.publicKey,
           tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
           whitelist: whitelistPda,
         })
         .rpc();
@@ -223,6 +425,7 @@ describe("Access Control Features", () => {
           authority: authority.publicKey,
           mint: mintKeypair.publicKey,
           tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
         })
         .rpc();
 
@@ -236,12 +439,33 @@ describe("Access Control Features", () => {
           authority: authority.publicKey,
           mint: mintKeypair.publicKey,
           tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
         })
         .rpc();
 
       tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
       assert.isFalse(tokenInfo.whitelistEnabled);
     });
+
+    it("Should close an entry and return its rent", async () => {
+      const receiver = Keypair.generate().publicKey;
+      const rent = await provider.connection.getBalance(whitelistPda);
+
+      await program.methods
+        .closeWhitelistEntry(investor)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+          whitelist: whitelistPda,
+          rentReceiver: receiver,
+        })
+        .rpc();
+
+      assert.isNull(await provider.connection.getAccountInfo(whitelistPda));
+      assert.equal(await provider.connection.getBalance(receiver), rent);
+    });
   });
 
   describe("Trading Control", () => {
@@ -253,6 +477,7 @@ describe("Access Control Features", () => {
           authority: authority.publicKey,
           mint: mintKeypair.publicKey,
           tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.PAUSER, authority.publicKey),
         })
         .rpc();
 
@@ -266,6 +491,7 @@ describe("Access Control Features", () => {
           authority: authority.publicKey,
           mint: mintKeypair.publicKey,
           tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.PAUSER, authority.publicKey),
         })
         .rpc();
 
@@ -283,13 +509,1412 @@ describe("Access Control Features", () => {
             authority: unauthorized.publicKey,
             mint: mintKeypair.publicKey,
             tokenInfo: tokenInfoPda,
+            roleGrant: rolePda(Role.PAUSER, unauthorized.publicKey),
           })
           .signers([unauthorized])
           .rpc();
         
         assert.fail("Expected transaction to fail");
       } catch (error) {
-        assert.include(error.toString(), "UnauthorizedAccess");
+        assert.include(error.toString(), "AccountNotInitialized");
+      }
+    });
+  });
+
+  describe("Account Freeze", () => {
+    const holder = Keypair.generate();
+    let holderTokenAccount: PublicKey;
+    const SUSPECTED_COMPROMISE = 1;
+
+    const freezeAccounts = (signer: PublicKey) => ({
+      authority: signer,
+      mint: mintKeypair.publicKey,
+      tokenInfo: tokenInfoPda,
+      roleGrant: rolePda(Role.PAUSER, signer),
+      mintAuthority: mintAuthorityPda,
+      account: holderTokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+    before(async () => {
+      holderTokenAccount = await getAssociatedTokenAddress(
+        mintKeypair.publicKey,
+        holder.publicKey
+      );
+      const tx = new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          authority.publicKey,
+          holderTokenAccount,
+          holder.publicKey,
+          mintKeypair.publicKey
+        )
+      );
+      await provider.sendAndConfirm(tx);
+    });
+
+    it("Should freeze a single account and count it", async () => {
+      await program.methods
+        .freezeAccount(SUSPECTED_COMPROMISE)
+        .accounts(freezeAccounts(authority.publicKey))
+        .rpc();
+
+      const account = await getAccount(provider.connection, holderTokenAccount);
+      assert.isTrue(account.isFrozen);
+      const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+      assert.equal(tokenInfo.frozenAccounts.toNumber(), 1);
+      // Trading stays on for everyone else
+      assert.isTrue(tokenInfo.tradingEnabled);
+
+      try {
+        await program.methods
+          .freezeAccount(SUSPECTED_COMPROMISE)
+          .accounts(freezeAccounts(authority.publicKey))
+          .rpc();
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "AccountAlreadyFrozen");
+      }
+    });
+
+    it("Should only let pausers thaw", async () => {
+      const unauthorized = Keypair.generate();
+      try {
+        await program.methods
+          .thawAccount(SUSPECTED_COMPROMISE)
+          .accounts(freezeAccounts(unauthorized.publicKey))
+          .signers([unauthorized])
+          .rpc();
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "AccountNotInitialized");
+      }
+    });
+
+    it("Should thaw the account and update the count", async () => {
+      await program.methods
+        .thawAccount(SUSPECTED_COMPROMISE)
+        .accounts(freezeAccounts(authority.publicKey))
+        .rpc();
+
+      const account = await getAccount(provider.connection, holderTokenAccount);
+      assert.isFalse(account.isFrozen);
+      const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+      assert.equal(tokenInfo.frozenAccounts.toNumber(), 0);
+
+      try {
+        await program.methods
+          .thawAccount(SUSPECTED_COMPROMISE)
+          .accounts(freezeAccounts(authority.publicKey))
+          .rpc();
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "AccountNotFrozen");
+      }
+    });
+  });
+
+  describe("Role Separation", () => {
+    const opsStaff = Keypair.generate();
+
+    before(async () => {
+      await grantRole(Role.PAUSER, opsStaff.publicKey);
+    });
+
+    it("Should let a pauser toggle trading", async () => {
+      await program.methods
+        .toggleTrading(false)
+        .accounts({
+          authority: opsStaff.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.PAUSER, opsStaff.publicKey),
+        })
+        .signers([opsStaff])
+        .rpc();
+
+      await program.methods
+        .toggleTrading(true)
+        .accounts({
+          authority: opsStaff.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.PAUSER, opsStaff.publicKey),
+        })
+        .signers([opsStaff])
+        .rpc();
+
+      const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+      assert.isTrue(tokenInfo.tradingEnabled);
+    });
+
+    it("Should not let a pauser use another role's grant", async () => {
+      try {
+        await program.methods
+          .toggleWhitelistMode(true)
+          .accounts({
+            authority: opsStaff.publicKey,
+            mint: mintKeypair.publicKey,
+            tokenInfo: tokenInfoPda,
+            roleGrant: rolePda(Role.PAUSER, opsStaff.publicKey),
+          })
+          .signers([opsStaff])
+          .rpc();
+
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "ConstraintSeeds");
+      }
+    });
+
+    it("Should stop honouring a revoked role", async () => {
+      await program.methods
+        .revokeRole()
+        .accounts({
+          admin: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          adminGrant: null,
+          roleGrant: rolePda(Role.PAUSER, opsStaff.publicKey),
+        })
+        .rpc();
+
+      try {
+        await program.methods
+          .toggleTrading(false)
+          .accounts({
+            authority: opsStaff.publicKey,
+            mint: mintKeypair.publicKey,
+            tokenInfo: tokenInfoPda,
+            roleGrant: rolePda(Role.PAUSER, opsStaff.publicKey),
+          })
+          .signers([opsStaff])
+          .rpc();
+
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "AccountNotInitialized");
+      }
+    });
+  });
+
+  describe("Controlled Transfer", () => {
+    const recipient = Keypair.generate();
+    let recipientTokenAccount: PublicKey;
+
+    const compliancePda = (prefix: string, wallet: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from(prefix), mintKeypair.publicKey.toBuffer(), wallet.toBuffer()],
+        program.programId
+      )[0];
+
+    const controlledTransfer = (recipientBlacklist: PublicKey) =>
+      program.methods
+        .controlledTransfer(new anchor.BN(1_000_000_000), NO_PROOFS)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          from: authorityTokenAccount,
+          to: recipientTokenAccount,
+          senderBlacklist: compliancePda("blacklist", authority.publicKey),
+          recipientBlacklist,
+          senderWhitelist: compliancePda("whitelist", authority.publicKey),
+          recipientWhitelist: compliancePda("whitelist", recipient.publicKey),
+          senderLimitExemption: limitExemptionPda(authorityTokenAccount),
+          recipientLimitExemption: limitExemptionPda(recipientTokenAccount),
+          senderFeeExemption: feeExemptionPda(authorityTokenAccount),
+          recipientFeeExemption: feeExemptionPda(recipientTokenAccount),
+          feeCollector: null,
+          senderVelocity: velocityPda(authority.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    before(async () => {
+      recipientTokenAccount = await getAssociatedTokenAddress(
+        mintKeypair.publicKey,
+        recipient.publicKey
+      );
+      const tx = new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          authority.publicKey,
+          recipientTokenAccount,
+          recipient.publicKey,
+          mintKeypair.publicKey
+        )
+      );
+      await provider.sendAndConfirm(tx);
+    });
+
+    it("Should treat never-created entries as not listed", async () => {
+      await controlledTransfer(compliancePda("blacklist", recipient.publicKey));
+    });
+
+    it("Should block a blacklisted recipient", async () => {
+      await program.methods
+        .addToBlacklist(recipient.publicKey, REASON_BOT, "", null, NO_REFERENCE)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+          blacklist: compliancePda("blacklist", recipient.publicKey),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      try {
+        await controlledTransfer(compliancePda("blacklist", recipient.publicKey));
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "RecipientBlacklisted");
+      }
+    });
+
+    it("Should reject another wallet's entry in place of the recipient's", async () => {
+      try {
+        await controlledTransfer(compliancePda("blacklist", Keypair.generate().publicKey));
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "ConstraintSeeds");
+      }
+    });
+
+    it("Should stop blocking once a temporary entry expires", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .addToBlacklist(recipient.publicKey, REASON_BOT, "Cooling-off period", new anchor.BN(now + 3), NO_REFERENCE)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+          blacklist: compliancePda("blacklist", recipient.publicKey),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      try {
+        await controlledTransfer(compliancePda("blacklist", recipient.publicKey));
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "RecipientBlacklisted");
+      }
+
+      await sleep(5000);
+      await controlledTransfer(compliancePda("blacklist", recipient.publicKey));
+    });
+
+    it("Should treat a closed entry as not listed", async () => {
+      await program.methods
+        .addToBlacklist(recipient.publicKey, REASON_BOT, "", null, NO_REFERENCE)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+          blacklist: compliancePda("blacklist", recipient.publicKey),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      await program.methods
+        .closeBlacklistEntry(recipient.publicKey)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+          blacklist: compliancePda("blacklist", recipient.publicKey),
+          rentReceiver: authority.publicKey,
+        })
+        .rpc();
+
+      await controlledTransfer(compliancePda("blacklist", recipient.publicKey));
+    });
+  });
+
+  describe("Transfer Limits", () => {
+    const holder = Keypair.generate();
+    let holderTokenAccount: PublicKey;
+    const oneToken = new anchor.BN(1_000_000_000);
+    const TRANSFER_LIMITS_DELAY = 24 * 60 * 60;
+
+    const queueAction = (queuedActionPda: PublicKey, action: any) =>
+      program.methods
+        .queueAction(action)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          queuedAction: queuedActionPda,
+          roleGrant: null,
+          liquidityPool: null,
+          treasury: null,
+          treasuryPool: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    const raiseTransferLimits = (maxTxAmount: anchor.BN | null, maxWalletBalance: anchor.BN | null) =>
+      program.methods
+        .raiseTransferLimits(maxTxAmount, maxWalletBalance)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.TREASURER, authority.publicKey),
+        })
+        .rpc();
+
+    before(async () => {
+      holderTokenAccount = await getAssociatedTokenAddress(
+        mintKeypair.publicKey,
+        holder.publicKey
+      );
+      const tx = new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          authority.publicKey,
+          holderTokenAccount,
+          holder.publicKey,
+          mintKeypair.publicKey
+        )
+      );
+      await provider.sendAndConfirm(tx);
+    });
+
+    it("Should not add a limit without the queue", async () => {
+      try {
+        await raiseTransferLimits(oneToken, null);
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "TransferLimitDecreaseRequiresQueue");
+      }
+    });
+
+    it("Should queue new limits behind the minimum delay", async () => {
+      const { queuedActionCount } = await program.account.tokenInfo.fetch(tokenInfoPda);
+      const [queuedActionPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("queued_action"),
+          mintKeypair.publicKey.toBuffer(),
+          queuedActionCount.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+      const [timelockAuthorityPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("timelock_authority"), mintKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await queueAction(queuedActionPda, {
+        setTransferLimits: {
+          maxTxAmount: oneToken.muln(2),
+          maxWalletBalance: oneToken.muln(3),
+        },
+      });
+
+      // Trading has no delay on this mint, but limits wait out their own minimum
+      const queued = await program.account.queuedAction.fetch(queuedActionPda);
+      assert.equal(queued.eta.toNumber() - queued.queuedAt.toNumber(), TRANSFER_LIMITS_DELAY);
+
+      try {
+        await program.methods
+          .executeQueuedAction()
+          .accounts({
+            executor: authority.publicKey,
+            queuedBy: authority.publicKey,
+            mint: mintKeypair.publicKey,
+            tokenInfo: tokenInfoPda,
+            queuedAction: queuedActionPda,
+            mintAuthority: mintAuthorityPda,
+            timelockAuthority: timelockAuthorityPda,
+            destination: null,
+            destinationWhitelist: null,
+            bucket: null,
+            liquidityPool: null,
+            treasury: null,
+            treasuryPool: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "TimelockNotElapsed");
+      }
+
+      const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+      assert.isNull(tokenInfo.maxTxAmount);
+      assert.isNull(tokenInfo.maxWalletBalance);
+    });
+
+    it("Should not queue a limits delay below the minimum", async () => {
+      const { queuedActionCount } = await program.account.tokenInfo.fetch(tokenInfoPda);
+      const [queuedActionPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("queued_action"),
+          mintKeypair.publicKey.toBuffer(),
+          queuedActionCount.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+      try {
+        await queueAction(queuedActionPda, {
+          setTimelockDelay: { kind: { transferLimits: {} }, delay: new anchor.BN(0) },
+        });
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidTimelockDelay");
+      }
+    });
+
+    it("Should add and remove limit exemptions", async () => {
+      await program.methods
+        .addLimitExemption()
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.TREASURER, authority.publicKey),
+          account: holderTokenAccount,
+          exemption: limitExemptionPda(holderTokenAccount),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const exemption = await program.account.transferLimitExemption.fetch(
+        limitExemptionPda(holderTokenAccount)
+      );
+      assert.ok(exemption.account.equals(holderTokenAccount));
+
+      await program.methods
+        .removeLimitExemption()
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.TREASURER, authority.publicKey),
+          exemption: limitExemptionPda(holderTokenAccount),
+        })
+        .rpc();
+
+      assert.isNull(
+        await provider.connection.getAccountInfo(limitExemptionPda(holderTokenAccount))
+      );
+    });
+
+    it("Should not add a limit exemption without the Treasurer role", async () => {
+      const outsider = Keypair.generate();
+      const sig = await provider.connection.requestAirdrop(
+        outsider.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(sig);
+
+      try {
+        await program.methods
+          .addLimitExemption()
+          .accounts({
+            authority: outsider.publicKey,
+            mint: mintKeypair.publicKey,
+            tokenInfo: tokenInfoPda,
+            roleGrant: rolePda(Role.TREASURER, outsider.publicKey),
+            account: holderTokenAccount,
+            exemption: limitExemptionPda(holderTokenAccount),
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([outsider])
+          .rpc();
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "AccountNotInitialized");
+      }
+    });
+  });
+
+  describe("Transfer Fees", () => {
+    const holder = Keypair.generate();
+    const collector = Keypair.generate();
+    let holderTokenAccount: PublicKey;
+    let collectorTokenAccount: PublicKey;
+    const oneToken = new anchor.BN(1_000_000_000);
+    // 2.5%, capped at 0.01 TDL per transfer
+    const FEE_BPS = 250;
+    const MAXIMUM_FEE = new anchor.BN(10_000_000);
+
+    const compliancePda = (prefix: string, wallet: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from(prefix), mintKeypair.publicKey.toBuffer(), wallet.toBuffer()],
+        program.programId
+      )[0];
+
+    const balance = async (account: PublicKey) =>
+      Number((await getAccount(provider.connection, account)).amount);
+
+    const sendToHolder = (amount: anchor.BN, feeCollector: PublicKey | null) =>
+      program.methods
+        .controlledTransfer(amount, NO_PROOFS)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          from: authorityTokenAccount,
+          to: holderTokenAccount,
+          senderBlacklist: compliancePda("blacklist", authority.publicKey),
+          recipientBlacklist: compliancePda("blacklist", holder.publicKey),
+          senderWhitelist: compliancePda("whitelist", authority.publicKey),
+          recipientWhitelist: compliancePda("whitelist", holder.publicKey),
+          senderLimitExemption: limitExemptionPda(authorityTokenAccount),
+          recipientLimitExemption: limitExemptionPda(holderTokenAccount),
+          senderFeeExemption: feeExemptionPda(authorityTokenAccount),
+          recipientFeeExemption: feeExemptionPda(holderTokenAccount),
+          feeCollector,
+          senderVelocity: velocityPda(authority.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    const configureTransferFee = (basisPoints: number, maximumFee: anchor.BN) =>
+      program.methods
+        .configureTransferFee(basisPoints, maximumFee)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.TREASURER, authority.publicKey),
+          mintAuthority: mintAuthorityPda,
+          feeCollector: collectorTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    before(async () => {
+      holderTokenAccount = await getAssociatedTokenAddress(
+        mintKeypair.publicKey,
+        holder.publicKey
+      );
+      collectorTokenAccount = await getAssociatedTokenAddress(
+        mintKeypair.publicKey,
+        collector.publicKey
+      );
+      const tx = new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          authority.publicKey,
+          holderTokenAccount,
+          holder.publicKey,
+          mintKeypair.publicKey
+        ),
+        createAssociatedTokenAccountInstruction(
+          authority.publicKey,
+          collectorTokenAccount,
+          collector.publicKey,
+          mintKeypair.publicKey
+        )
+      );
+      await provider.sendAndConfirm(tx);
+    });
+
+    it("Should reject a fee above 100%", async () => {
+      try {
+        await configureTransferFee(10_001, MAXIMUM_FEE);
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidTransferFee");
+      }
+    });
+
+    it("Should pay the fee to the collector and the rest to the recipient", async () => {
+      await configureTransferFee(FEE_BPS, MAXIMUM_FEE);
+      const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+      assert.equal(tokenInfo.transferFeeBasisPoints, FEE_BPS);
+      assert.ok(tokenInfo.feeCollector.equals(collectorTokenAccount));
+
+      // 2.5% of 0.1 TDL stays under the cap
+      await sendToHolder(oneToken.divn(10), collectorTokenAccount);
+      assert.equal(await balance(holderTokenAccount), 97_500_000);
+      assert.equal(await balance(collectorTokenAccount), 2_500_000);
+
+      // 2.5% of 1 TDL is capped at the maximum fee
+      await sendToHolder(oneToken, collectorTokenAccount);
+      assert.equal(await balance(holderTokenAccount), 97_500_000 + 990_000_000);
+      assert.equal(await balance(collectorTokenAccount), 12_500_000);
+    });
+
+    it("Should require the fee collector while a fee applies", async () => {
+      try {
+        await sendToHolder(oneToken, null);
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "FeeCollectorMismatch");
+      }
+
+      try {
+        await sendToHolder(oneToken, holderTokenAccount);
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "FeeCollectorMismatch");
+      }
+    });
+
+    it("Should not add a fee exemption without the Treasurer role", async () => {
+      const outsider = Keypair.generate();
+      const sig = await provider.connection.requestAirdrop(
+        outsider.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(sig);
+
+      try {
+        await program.methods
+          .addFeeExemption()
+          .accounts({
+            authority: outsider.publicKey,
+            mint: mintKeypair.publicKey,
+            tokenInfo: tokenInfoPda,
+            roleGrant: rolePda(Role.TREASURER, outsider.publicKey),
+            account: holderTokenAccount,
+            exemption: feeExemptionPda(holderTokenAccount),
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([outsider])
+          .rpc();
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "AccountNotInitialized");
+      }
+    });
+
+    it("Should not charge exempt accounts", async () => {
+      await program.methods
+        .addFeeExemption()
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.TREASURER, authority.publicKey),
+          account: holderTokenAccount,
+          exemption: feeExemptionPda(holderTokenAccount),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const before = await balance(holderTokenAccount);
+      await sendToHolder(oneToken, null);
+      assert.equal((await balance(holderTokenAccount)) - before, oneToken.toNumber());
+      assert.equal(await balance(collectorTokenAccount), 12_500_000);
+
+      await program.methods
+        .removeFeeExemption()
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.TREASURER, authority.publicKey),
+          exemption: feeExemptionPda(holderTokenAccount),
+        })
+        .rpc();
+
+      // Later transfers in this suite pass no collector
+      await configureTransferFee(0, new anchor.BN(0));
+    });
+  });
+
+  describe("Velocity Limits", () => {
+    const sender = Keypair.generate();
+    let senderTokenAccount: PublicKey;
+    const oneToken = new anchor.BN(1_000_000_000);
+    const VELOCITY_LIMITS_DELAY = 24 * 60 * 60;
+
+    const compliancePda = (prefix: string, wallet: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from(prefix), mintKeypair.publicKey.toBuffer(), wallet.toBuffer()],
+        program.programId
+      )[0];
+
+    const transferAccounts = (owner: PublicKey, from: PublicKey, to: PublicKey, toOwner: PublicKey) => ({
+      authority: owner,
+      mint: mintKeypair.publicKey,
+      tokenInfo: tokenInfoPda,
+      from,
+      to,
+      senderBlacklist: compliancePda("blacklist", owner),
+      recipientBlacklist: compliancePda("blacklist", toOwner),
+      senderWhitelist: compliancePda("whitelist", owner),
+      recipientWhitelist: compliancePda("whitelist", toOwner),
+      senderLimitExemption: limitExemptionPda(from),
+      recipientLimitExemption: limitExemptionPda(to),
+      senderFeeExemption: feeExemptionPda(from),
+      recipientFeeExemption: feeExemptionPda(to),
+      feeCollector: null,
+      senderVelocity: velocityPda(owner),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+
+    const sendFromSender = (amount: anchor.BN) =>
+      program.methods
+        .controlledTransfer(amount, NO_PROOFS)
+        .accounts(
+          transferAccounts(sender.publicKey, senderTokenAccount, authorityTokenAccount, authority.publicKey)
+        )
+        .signers([sender])
+        .rpc();
+
+    const raiseVelocityLimits = (limit: anchor.BN | null, whitelistedLimit: anchor.BN | null) =>
+      program.methods
+        .raiseVelocityLimits(limit, whitelistedLimit)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.TREASURER, authority.publicKey),
+        })
+        .rpc();
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(
+        sender.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(sig);
+
+      senderTokenAccount = await getAssociatedTokenAddress(
+        mintKeypair.publicKey,
+        sender.publicKey
+      );
+      const tx = new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          authority.publicKey,
+          senderTokenAccount,
+          sender.publicKey,
+          mintKeypair.publicKey
+        )
+      );
+      await provider.sendAndConfirm(tx);
+
+      // Funded before any limit applies to the authority's own outflow
+      await program.methods
+        .controlledTransfer(oneToken.muln(10), NO_PROOFS)
+        .accounts(
+          transferAccounts(authority.publicKey, authorityTokenAccount, senderTokenAccount, sender.publicKey)
+        )
+        .rpc();
+    });
+
+    it("Should not add a velocity limit without the queue", async () => {
+      try {
+        await raiseVelocityLimits(oneToken.muln(2), null);
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "TransferLimitDecreaseRequiresQueue");
+      }
+    });
+
+    it("Should not raise velocity limits without the Treasurer role", async () => {
+      try {
+        await program.methods
+          .raiseVelocityLimits(null, null)
+          .accounts({
+            authority: sender.publicKey,
+            mint: mintKeypair.publicKey,
+            tokenInfo: tokenInfoPda,
+            roleGrant: rolePda(Role.TREASURER, sender.publicKey),
+          })
+          .signers([sender])
+          .rpc();
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "AccountNotInitialized");
+      }
+    });
+
+    it("Should queue velocity limits behind the minimum delay", async () => {
+      const { queuedActionCount } = await program.account.tokenInfo.fetch(tokenInfoPda);
+      const [queuedActionPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("queued_action"),
+          mintKeypair.publicKey.toBuffer(),
+          queuedActionCount.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+      const [timelockAuthorityPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("timelock_authority"), mintKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .queueAction({
+          setVelocityLimits: {
+            velocityLimit: oneToken.muln(2),
+            whitelistedVelocityLimit: oneToken.muln(5),
+          },
+        })
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          queuedAction: queuedActionPda,
+          roleGrant: null,
+          liquidityPool: null,
+          treasury: null,
+          treasuryPool: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const queued = await program.account.queuedAction.fetch(queuedActionPda);
+      assert.equal(queued.eta.toNumber() - queued.queuedAt.toNumber(), VELOCITY_LIMITS_DELAY);
+
+      try {
+        await program.methods
+          .executeQueuedAction()
+          .accounts({
+            executor: authority.publicKey,
+            queuedBy: authority.publicKey,
+            mint: mintKeypair.publicKey,
+            tokenInfo: tokenInfoPda,
+            queuedAction: queuedActionPda,
+            mintAuthority: mintAuthorityPda,
+            timelockAuthority: timelockAuthorityPda,
+            destination: null,
+            destinationWhitelist: null,
+            bucket: null,
+            liquidityPool: null,
+            treasury: null,
+            treasuryPool: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "TimelockNotElapsed");
+      }
+
+      const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+      assert.isNull(tokenInfo.velocityLimit);
+      assert.isNull(tokenInfo.whitelistedVelocityLimit);
+    });
+
+    it("Should not track outflow until a limit applies", async () => {
+      await sendFromSender(oneToken);
+      await sendFromSender(oneToken);
+      assert.isNull(await provider.connection.getAccountInfo(velocityPda(sender.publicKey)));
+    });
+  });
+
+  describe("Merkle Lists", () => {
+    const recipient = Keypair.generate();
+    let recipientTokenAccount: PublicKey;
+
+    const compliancePda = (prefix: string, wallet: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from(prefix), mintKeypair.publicKey.toBuffer(), wallet.toBuffer()],
+        program.programId
+      )[0];
+
+    const transferWithProofs = (proofs: any) =>
+      program.methods
+        .controlledTransfer(new anchor.BN(1_000_000_000), proofs)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          from: authorityTokenAccount,
+          to: recipientTokenAccount,
+          senderBlacklist: compliancePda("blacklist", authority.publicKey),
+          recipientBlacklist: compliancePda("blacklist", recipient.publicKey),
+          senderWhitelist: compliancePda("whitelist", authority.publicKey),
+          recipientWhitelist: compliancePda("whitelist", recipient.publicKey),
+          senderLimitExemption: limitExemptionPda(authorityTokenAccount),
+          recipientLimitExemption: limitExemptionPda(recipientTokenAccount),
+          senderFeeExemption: feeExemptionPda(authorityTokenAccount),
+          recipientFeeExemption: feeExemptionPda(recipientTokenAccount),
+          feeCollector: null,
+          senderVelocity: velocityPda(authority.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    const setRoot = (list: "whitelist" | "blacklist", root: Uint8Array) =>
+      (list === "whitelist"
+        ? program.methods.setWhitelistRoot(Array.from(root))
+        : program.methods.setBlacklistRoot(Array.from(root))
+      )
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+        })
+        .rpc();
+
+    before(async () => {
+      recipientTokenAccount = await getAssociatedTokenAddress(
+        mintKeypair.publicKey,
+        recipient.publicKey
+      );
+      const tx = new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          authority.publicKey,
+          recipientTokenAccount,
+          recipient.publicKey,
+          mintKeypair.publicKey
+        )
+      );
+      await provider.sendAndConfirm(tx);
+    });
+
+    it("Should require exclusion proofs while a blacklist root is set", async () => {
+      const listed = [Keypair.generate(), Keypair.generate(), Keypair.generate()].map(
+        (keypair) => keypair.publicKey
+      );
+      const tree = computeBlacklistArtifacts(listed);
+      await setRoot("blacklist", tree.root);
+
+      const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+      assert.equal(tokenInfo.blacklistRootVersion, 1);
+
+      try {
+        await transferWithProofs(NO_PROOFS);
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidExclusionProof");
+      }
+
+      await transferWithProofs({
+        ...NO_PROOFS,
+        senderExclusion: tree.exclusionProof(authority.publicKey),
+        recipientExclusion: tree.exclusionProof(recipient.publicKey),
+      });
+    });
+
+    it("Should block a wallet once a rotated root lists it", async () => {
+      const tree = computeBlacklistArtifacts([recipient.publicKey]);
+      assert.isNull(tree.exclusionProof(recipient.publicKey));
+      await setRoot("blacklist", tree.root);
+
+      // A proof against the old tree no longer verifies
+      const stale = computeBlacklistArtifacts([]);
+      try {
+        await transferWithProofs({
+          ...NO_PROOFS,
+          senderExclusion: tree.exclusionProof(authority.publicKey),
+          recipientExclusion: stale.exclusionProof(recipient.publicKey),
+        });
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidExclusionProof");
+      }
+
+      await setRoot("blacklist", new Uint8Array(32));
+      await transferWithProofs(NO_PROOFS);
+    });
+
+    it("Should accept whitelist membership proofs in whitelist mode", async () => {
+      const { root, proofs } = computeMerkleArtifacts([
+        authority.publicKey,
+        recipient.publicKey,
+        Keypair.generate().publicKey,
+      ]);
+      const proofFor = (wallet: PublicKey) =>
+        proofs[wallet.toBase58()].map((node) => Array.from(Buffer.from(node, "hex")));
+
+      await program.methods
+        .toggleWhitelistMode(true)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+        })
+        .rpc();
+      await setRoot("whitelist", root);
+
+      try {
+        await transferWithProofs(NO_PROOFS);
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "SenderNotWhitelisted");
+      }
+
+      await transferWithProofs({
+        ...NO_PROOFS,
+        senderWhitelist: proofFor(authority.publicKey),
+        recipientWhitelist: proofFor(recipient.publicKey),
+      });
+
+      await setRoot("whitelist", new Uint8Array(32));
+      await program.methods
+        .toggleWhitelistMode(false)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+        })
+        .rpc();
+    });
+  });
+
+  describe("Whitelist Allocations", () => {
+    const investor = Keypair.generate();
+    let investorTokenAccount: PublicKey;
+    const oneToken = new anchor.BN(1_000_000_000);
+
+    const setWhitelistMode = (enabled: boolean) =>
+      program.methods
+        .toggleWhitelistMode(enabled)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+        })
+        .rpc();
+
+    const whitelist = (wallet: PublicKey, allocation: anchor.BN) =>
+      program.methods
+        .addToWhitelist(wallet, allocation)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+          whitelist: whitelistPda(wallet),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    const sendToInvestor = () =>
+      program.methods
+        .controlledTransfer(oneToken, NO_PROOFS)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          from: authorityTokenAccount,
+          to: investorTokenAccount,
+          senderBlacklist: PublicKey.findProgramAddressSync(
+            [Buffer.from("blacklist"), mintKeypair.publicKey.toBuffer(), authority.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          recipientBlacklist: PublicKey.findProgramAddressSync(
+            [Buffer.from("blacklist"), mintKeypair.publicKey.toBuffer(), investor.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          senderWhitelist: whitelistPda(authority.publicKey),
+          recipientWhitelist: whitelistPda(investor.publicKey),
+          senderLimitExemption: limitExemptionPda(authorityTokenAccount),
+          recipientLimitExemption: limitExemptionPda(investorTokenAccount),
+          senderFeeExemption: feeExemptionPda(authorityTokenAccount),
+          recipientFeeExemption: feeExemptionPda(investorTokenAccount),
+          feeCollector: null,
+          senderVelocity: velocityPda(authority.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    before(async () => {
+      investorTokenAccount = await getAssociatedTokenAddress(
+        mintKeypair.publicKey,
+        investor.publicKey
+      );
+      const tx = new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          authority.publicKey,
+          investorTokenAccount,
+          investor.publicKey,
+          mintKeypair.publicKey
+        )
+      );
+      await provider.sendAndConfirm(tx);
+
+      // Whitelist mode requires the sender to be listed as well
+      await whitelist(authority.publicKey, new anchor.BN(0));
+      await whitelist(investor.publicKey, oneToken);
+      await setWhitelistMode(true);
+    });
+
+    after(async () => {
+      await setWhitelistMode(false);
+    });
+
+    it("Should count transfers against the recipient allocation", async () => {
+      await sendToInvestor();
+
+      const entry = await program.account.whitelistEntry.fetch(whitelistPda(investor.publicKey));
+      assert.equal(entry.purchased.toString(), oneToken.toString());
+
+      try {
+        await sendToInvestor();
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "AllocationExceeded");
+      }
+    });
+
+    it("Should allow more after a top-up", async () => {
+      await program.methods
+        .topUpAllocation(investor.publicKey, oneToken)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+          whitelist: whitelistPda(investor.publicKey),
+        })
+        .rpc();
+
+      await sendToInvestor();
+
+      const entry = await program.account.whitelistEntry.fetch(whitelistPda(investor.publicKey));
+      assert.equal(entry.allocation.toString(), oneToken.muln(2).toString());
+      assert.equal(entry.purchased.toString(), oneToken.muln(2).toString());
+    });
+
+    it("Should clear purchases on reset", async () => {
+      await program.methods
+        .resetAllocation(investor.publicKey, oneToken)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+          whitelist: whitelistPda(investor.publicKey),
+        })
+        .rpc();
+
+      const entry = await program.account.whitelistEntry.fetch(whitelistPda(investor.publicKey));
+      assert.equal(entry.allocation.toString(), oneToken.toString());
+      assert.equal(entry.purchased.toNumber(), 0);
+    });
+  });
+
+  describe("Batch Management", () => {
+    const wallets = [Keypair.generate(), Keypair.generate(), Keypair.generate()].map(
+      (keypair) => keypair.publicKey
+    );
+
+    const entryMetas = (prefix: string, keys: PublicKey[]) =>
+      keys.map((wallet) => ({
+        pubkey: PublicKey.findProgramAddressSync(
+          [Buffer.from(prefix), mintKeypair.publicKey.toBuffer(), wallet.toBuffer()],
+          program.programId
+        )[0],
+        isSigner: false,
+        isWritable: true,
+      }));
+
+    const batchAccounts = () => ({
+      authority: authority.publicKey,
+      mint: mintKeypair.publicKey,
+      tokenInfo: tokenInfoPda,
+      roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+      systemProgram: SystemProgram.programId,
+    });
+
+    it("Should whitelist and remove several wallets in one transaction", async () => {
+      const allocations = [1, 2, 3].map((n) => new anchor.BN(n * 1_000_000_000));
+      const metas = entryMetas("whitelist", wallets);
+
+      await program.methods
+        .batchAddToWhitelist(wallets, allocations)
+        .accounts(batchAccounts())
+        .remainingAccounts(metas)
+        .rpc();
+
+      for (let i = 0; i < wallets.length; i++) {
+        const entry = await program.account.whitelistEntry.fetch(metas[i].pubkey);
+        assert.equal(entry.wallet.toString(), wallets[i].toString());
+        assert.isTrue(entry.isWhitelisted);
+        assert.equal(entry.allocation.toString(), allocations[i].toString());
+      }
+
+      await program.methods
+        .batchRemoveFromWhitelist(wallets)
+        .accounts(batchAccounts())
+        .remainingAccounts(metas)
+        .rpc();
+
+      for (const meta of metas) {
+        const entry = await program.account.whitelistEntry.fetch(meta.pubkey);
+        assert.isFalse(entry.isWhitelisted);
+      }
+    });
+
+    it("Should blacklist and remove several wallets in one transaction", async () => {
+      const metas = entryMetas("blacklist", wallets);
+
+      await program.methods
+        .batchAddToBlacklist(wallets, REASON_BOT, "Launch sniper cluster", null, NO_REFERENCE)
+        .accounts(batchAccounts())
+        .remainingAccounts(metas)
+        .rpc();
+
+      for (const meta of metas) {
+        const entry = await program.account.blacklistEntry.fetch(meta.pubkey);
+        assert.isTrue(entry.isBlacklisted);
+        assert.equal(entry.reason, "Launch sniper cluster");
+      }
+
+      await program.methods
+        .batchRemoveFromBlacklist(wallets)
+        .accounts(batchAccounts())
+        .remainingAccounts(metas)
+        .rpc();
+
+      for (const meta of metas) {
+        const entry = await program.account.blacklistEntry.fetch(meta.pubkey);
+        assert.isFalse(entry.isBlacklisted);
+      }
+    });
+
+    it("Should reject a PDA that does not belong to its wallet", async () => {
+      try {
+        await program.methods
+          .batchAddToBlacklist(wallets.slice(0, 2), REASON_BOT, "", null, NO_REFERENCE)
+          .accounts(batchAccounts())
+          .remainingAccounts(entryMetas("blacklist", [wallets[1], wallets[0]]))
+          .rpc();
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidComplianceAccount");
+      }
+    });
+
+    it("Should reject a wallet list that does not match the accounts", async () => {
+      try {
+        await program.methods
+          .batchAddToWhitelist(wallets, [new anchor.BN(1)])
+          .accounts(batchAccounts())
+          .remainingAccounts(entryMetas("whitelist", wallets))
+          .rpc();
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "BatchLengthMismatch");
+      }
+    });
+  });
+
+  describe("Blacklist Appeals", () => {
+    const appellant = Keypair.generate();
+    let appellantBlacklistPda: PublicKey;
+
+    const blacklist = () =>
+      program.methods
+        .addToBlacklist(appellant.publicKey, REASON_BOT, "Flagged by monitoring", null, NO_REFERENCE)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+          blacklist: appellantBlacklistPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    const fileAppeal = () =>
+      program.methods
+        .fileAppeal("https://example.com/appeal.json", Array(32).fill(7))
+        .accounts({
+          wallet: appellant.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          blacklist: appellantBlacklistPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([appellant])
+        .rpc();
+
+    const resolveAppeal = (uphold: boolean) =>
+      program.methods
+        .resolveAppeal(appellant.publicKey, uphold)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+          blacklist: appellantBlacklistPda,
+        })
+        .rpc();
+
+    before(async () => {
+      [appellantBlacklistPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("blacklist"), mintKeypair.publicKey.toBuffer(), appellant.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const fundTx = new anchor.web3.Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: authority.publicKey,
+          toPubkey: appellant.publicKey,
+          lamports: anchor.web3.LAMPORTS_PER_SOL / 10,
+        })
+      );
+      await provider.sendAndConfirm(fundTx);
+    });
+
+    it("Should lift an entry when the appeal succeeds", async () => {
+      await blacklist();
+      await fileAppeal();
+
+      let entry = await program.account.blacklistEntry.fetch(appellantBlacklistPda);
+      assert.ok(entry.appeal.pending);
+      const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+      assert.isAtLeast(
+        entry.appeal.pending.deadline.toNumber(),
+        entry.addedAt.toNumber() + tokenInfo.appealWindow.toNumber()
+      );
+
+      await resolveAppeal(false);
+
+      entry = await program.account.blacklistEntry.fetch(appellantBlacklistPda);
+      assert.isFalse(entry.isBlacklisted);
+      assert.ok(entry.appeal.none);
+    });
+
+    it("Should keep an upheld entry and refuse a second appeal", async () => {
+      await blacklist();
+      await fileAppeal();
+      await resolveAppeal(true);
+
+      const entry = await program.account.blacklistEntry.fetch(appellantBlacklistPda);
+      assert.isTrue(entry.isBlacklisted);
+      assert.ok(entry.appeal.upheld);
+
+      try {
+        await fileAppeal();
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "AppealAlreadyFiled");
+      }
+    });
+
+    it("Should let an unanswered appeal lapse the entry", async () => {
+      await program.methods
+        .setAppealWindow(new anchor.BN(2))
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+        })
+        .rpc();
+
+      await blacklist();
+      await fileAppeal();
+      await sleep(4000);
+
+      try {
+        await resolveAppeal(true);
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "AppealWindowElapsed");
       }
     });
   });
@@ -318,6 +1943,7 @@ describe("Access Control Features", () => {
           authority: authority.publicKey,
           mint: mintKeypair.publicKey,
           tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
         })
         .rpc();
 
@@ -328,6 +1954,7 @@ describe("Access Control Features", () => {
           authority: authority.publicKey,
           mint: mintKeypair.publicKey,
           tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.PAUSER, authority.publicKey),
         })
         .rpc();
 
@@ -338,6 +1965,7 @@ describe("Access Control Features", () => {
           authority: authority.publicKey,
           mint: mintKeypair.publicKey,
           tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
         })
         .rpc();
 
@@ -357,6 +1985,7 @@ describe("Access Control Features", () => {
           authority: authority.publicKey,
           mint: mintKeypair.publicKey,
           tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
         })
         .rpc();
 
@@ -367,6 +1996,7 @@ describe("Access Control Features", () => {
           authority: authority.publicKey,
           mint: mintKeypair.publicKey,
           tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.PAUSER, authority.publicKey),
         })
         .rpc();
 
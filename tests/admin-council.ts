@@ -0,0 +1,362 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { DollarToken } from "../target/types/dollar_token";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  TransactionInstruction,
+  LAMPORTS_PER_SOL,
+} from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+
+// 100,000,000 TDL with 9 decimals
+const MAX_SUPPLY = new anchor.BN("100000000000000000");
+
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
+const metadataPda = (mint: PublicKey) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+
+// Role discriminants double as the role byte in grant PDA seeds
+const Role = {
+  MINTER: 0,
+  BURNER: 1,
+  COMPLIANCE_OFFICER: 2,
+  PAUSER: 3,
+  TREASURER: 4,
+  ROLE_ADMIN: 5,
+};
+const ROLE_ARGS = [
+  { minter: {} },
+  { burner: {} },
+  { complianceOfficer: {} },
+  { pauser: {} },
+  { treasurer: {} },
+  { roleAdmin: {} },
+];
+
+describe("Admin Council", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.DollarToken as Program<DollarToken>;
+  const authority = provider.wallet as anchor.Wallet;
+  const mintKeypair = Keypair.generate();
+  const secondMember = Keypair.generate();
+  const thirdMember = Keypair.generate();
+
+  let tokenInfoPda: PublicKey;
+  let mintAuthorityPda: PublicKey;
+  let councilPda: PublicKey;
+  let councilAuthorityPda: PublicKey;
+
+  const proposalPda = (index: number) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("council_proposal"),
+        councilPda.toBuffer(),
+        new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+  const rolePda = (role: number, holder: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("role"),
+        mintKeypair.publicKey.toBuffer(),
+        Buffer.from([role]),
+        holder.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  const grantRole = (role: number, holder: PublicKey) =>
+    program.methods
+      .grantRole(ROLE_ARGS[role] as any, holder, null)
+      .accounts({
+        admin: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        adminGrant: null,
+        roleGrant: rolePda(role, holder),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  // Records an instruction built by the client as a council proposal
+  const propose = async (index: number, ix: TransactionInstruction) => {
+    await program.methods
+      .createCouncilProposal(
+        ix.data,
+        ix.keys.map((key) => ({
+          pubkey: key.pubkey,
+          isSigner: key.isSigner,
+          isWritable: key.isWritable,
+        }))
+      )
+      .accounts({
+        proposer: authority.publicKey,
+        council: councilPda,
+        proposal: proposalPda(index),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  };
+
+  const execute = async (index: number, ix: TransactionInstruction) => {
+    await program.methods
+      .executeCouncilProposal()
+      .accounts({
+        executor: authority.publicKey,
+        council: councilPda,
+        councilAuthority: councilAuthorityPda,
+        proposal: proposalPda(index),
+        thisProgram: program.programId,
+      })
+      .remainingAccounts(
+        ix.keys.map((key) => ({
+          pubkey: key.pubkey,
+          isSigner: false,
+          isWritable: key.isWritable,
+        }))
+      )
+      .rpc();
+  };
+
+  before(async () => {
+    [tokenInfoPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_info"), mintKeypair.publicKey.toBuffer()],
+      program.programId
+    );
+    [mintAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_authority"), mintKeypair.publicKey.toBuffer()],
+      program.programId
+    );
+    [councilPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("admin_council"), mintKeypair.publicKey.toBuffer()],
+      program.programId
+    );
+    [councilAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("council_authority"), councilPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .initializeToken("Dollar Token", "TDL", "https://example.com/metadata.json", 9, MAX_SUPPLY)
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        mintAuthority: mintAuthorityPda,
+        tokenInfo: tokenInfoPda,
+        metadata: metadataPda(mintKeypair.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([mintKeypair])
+      .rpc();
+
+    await grantRole(Role.PAUSER, councilAuthorityPda);
+
+    // The council authority pays rent for accounts created by its proposals
+    const fundTx = new anchor.web3.Transaction().add(
+      SystemProgram.transfer({
+        fromPubkey: authority.publicKey,
+        toPubkey: councilAuthorityPda,
+        lamports: LAMPORTS_PER_SOL,
+      })
+    );
+    await provider.sendAndConfirm(fundTx);
+  });
+
+  it("Creates a 2-of-3 council", async () => {
+    await program.methods
+      .createAdminCouncil(
+        [authority.publicKey, secondMember.publicKey, thirdMember.publicKey],
+        2
+      )
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        council: councilPda,
+        councilAuthority: councilAuthorityPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const council = await program.account.adminCouncil.fetch(councilPda);
+    assert.equal(council.members.length, 3);
+    assert.equal(council.threshold, 2);
+    assert.equal(council.proposalCount.toNumber(), 0);
+  });
+
+  it("Hands the token authority to the council", async () => {
+    await program.methods
+      .proposeAuthority(councilAuthorityPda)
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+      })
+      .rpc();
+
+    const acceptIx = await program.methods
+      .acceptAuthority()
+      .accounts({
+        newAuthority: councilAuthorityPda,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+      })
+      .instruction();
+
+    await propose(0, acceptIx);
+
+    try {
+      await execute(0, acceptIx);
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "ProposalThresholdNotMet");
+    }
+
+    await program.methods
+      .approveCouncilProposal()
+      .accounts({
+        member: secondMember.publicKey,
+        council: councilPda,
+        proposal: proposalPda(0),
+      })
+      .signers([secondMember])
+      .rpc();
+
+    await execute(0, acceptIx);
+
+    const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+    assert.equal(tokenInfo.authority.toString(), councilAuthorityPda.toString());
+
+    const proposal = await program.account.councilProposal.fetch(proposalPda(0));
+    assert.isTrue(proposal.executed);
+  });
+
+  it("Executes admin instructions once the threshold is reached", async () => {
+    const toggleIx = await program.methods
+      .toggleTrading(false)
+      .accounts({
+        authority: councilAuthorityPda,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        roleGrant: rolePda(Role.PAUSER, councilAuthorityPda),
+      })
+      .instruction();
+
+    await propose(1, toggleIx);
+    await program.methods
+      .approveCouncilProposal()
+      .accounts({
+        member: thirdMember.publicKey,
+        council: councilPda,
+        proposal: proposalPda(1),
+      })
+      .signers([thirdMember])
+      .rpc();
+    await execute(1, toggleIx);
+
+    const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+    assert.isFalse(tokenInfo.tradingEnabled);
+  });
+
+  it("Does not replay an executed proposal", async () => {
+    const toggleIx = await program.methods
+      .toggleTrading(false)
+      .accounts({
+        authority: councilAuthorityPda,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        roleGrant: rolePda(Role.PAUSER, councilAuthorityPda),
+      })
+      .instruction();
+
+    try {
+      await execute(1, toggleIx);
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "ProposalAlreadyExecuted");
+    }
+  });
+
+  it("Rejects approvals from non-members", async () => {
+    const outsider = Keypair.generate();
+
+    try {
+      await program.methods
+        .approveCouncilProposal()
+        .accounts({
+          member: outsider.publicKey,
+          council: councilPda,
+          proposal: proposalPda(1),
+        })
+        .signers([outsider])
+        .rpc();
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "NotCouncilMember");
+    }
+  });
+
+  it("Drops approvals from members who left the council", async () => {
+    const fourthMember = Keypair.generate();
+    const approve = (member: Keypair, index: number) =>
+      program.methods
+        .approveCouncilProposal()
+        .accounts({
+          member: member.publicKey,
+          council: councilPda,
+          proposal: proposalPda(index),
+        })
+        .signers([member])
+        .rpc();
+
+    const toggleIx = await program.methods
+      .toggleTrading(true)
+      .accounts({
+        authority: councilAuthorityPda,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        roleGrant: rolePda(Role.PAUSER, councilAuthorityPda),
+      })
+      .instruction();
+    await propose(2, toggleIx);
+    await approve(thirdMember, 2);
+
+    // Rotate the third member out before proposal 2 executes
+    const rotateIx = await program.methods
+      .updateAdminCouncil(
+        [authority.publicKey, secondMember.publicKey, fourthMember.publicKey],
+        2
+      )
+      .accounts({ councilAuthority: councilAuthorityPda, council: councilPda })
+      .instruction();
+    await propose(3, rotateIx);
+    await approve(secondMember, 3);
+    await execute(3, rotateIx);
+
+    await approve(fourthMember, 2);
+    const proposal = await program.account.councilProposal.fetch(proposalPda(2));
+    assert.deepEqual(
+      proposal.approvals.map((key) => key.toString()),
+      [authority.publicKey.toString(), fourthMember.publicKey.toString()]
+    );
+
+    await execute(2, toggleIx);
+    const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+    assert.isTrue(tokenInfo.tradingEnabled);
+  });
+});
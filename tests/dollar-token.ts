@@ -12,9 +12,65 @@ import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
   getAssociatedTokenAddress,
   createAssociatedTokenAccountInstruction,
+  getMint,
+  getAccount,
+  createMint,
+  createAssociatedTokenAccount,
+  mintTo,
 } from "@solana/spl-token";
 import { assert } from "chai";
 
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+// 100,000,000 TDL with 9 decimals
+const MAX_SUPPLY = new anchor.BN("100000000000000000");
+
+// Mock LP tokens placed in the liquidity lock
+const LP_LOCKED = 1_000_000;
+
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
+const metadataPda = (mint: PublicKey) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+
+// Role discriminants double as the role byte in grant PDA seeds
+const Role = {
+  MINTER: 0,
+  BURNER: 1,
+  COMPLIANCE_OFFICER: 2,
+  PAUSER: 3,
+  TREASURER: 4,
+  ROLE_ADMIN: 5,
+};
+const ROLE_ARGS = [
+  { minter: {} },
+  { burner: {} },
+  { complianceOfficer: {} },
+  { pauser: {} },
+  { treasurer: {} },
+  { roleAdmin: {} },
+];
+
+// Bucket discriminants double as the category byte in bucket PDA seeds
+const Bucket = {
+  PRESALE: 0,
+  LIQUIDITY: 1,
+  REWARDS: 2,
+  TEAM: 3,
+  MARKETING: 4,
+};
+const BUCKET_ARGS = [
+  { presale: {} },
+  { liquidity: {} },
+  { rewards: {} },
+  { team: {} },
+  { marketing: {} },
+];
+
 describe("dollar-token", () => {
   // Configure the client to use the local cluster.
   const provider = anchor.AnchorProvider.env();
@@ -28,7 +84,119 @@ describe("dollar-token", () => {
   
   let tokenInfoPda: PublicKey;
   let tokenInfoBump: number;
+  let mintAuthorityPda: PublicKey;
   let authorityTokenAccount: PublicKey;
+  let lpMint: PublicKey;
+  let lpSource: PublicKey;
+  let liquidityLockPda: PublicKey;
+  let vaultPda: PublicKey;
+
+  const rolePda = (role: number, holder: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("role"),
+        mintKeypair.publicKey.toBuffer(),
+        Buffer.from([role]),
+        holder.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  const whitelistPda = (wallet: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("whitelist"), mintKeypair.publicKey.toBuffer(), wallet.toBuffer()],
+      program.programId
+    )[0];
+
+  const bucketPda = (category: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("bucket"), mintKeypair.publicKey.toBuffer(), Buffer.from([category])],
+      program.programId
+    )[0];
+
+  // Creates a bucket paying into `destination` and releases `released` of it
+  const openBucket = async (
+    category: number,
+    cap: anchor.BN,
+    destination: PublicKey,
+    released: anchor.BN
+  ) => {
+    await program.methods
+      .createAllocationBucket(BUCKET_ARGS[category] as any, cap, destination)
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        roleGrant: rolePda(Role.MINTER, authority.publicKey),
+        bucket: bucketPda(category),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .releaseAllocationBucket(released)
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        roleGrant: rolePda(Role.MINTER, authority.publicKey),
+        bucket: bucketPda(category),
+      })
+      .rpc();
+  };
+
+  const grantRole = (role: number, holder: PublicKey) =>
+    program.methods
+      .grantRole(ROLE_ARGS[role] as any, holder, null)
+      .accounts({
+        admin: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        adminGrant: null,
+        roleGrant: rolePda(role, holder),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  const now = () => Math.floor(Date.now() / 1000);
+
+  const lockAccounts = () => ({
+    authority: authority.publicKey,
+    mint: mintKeypair.publicKey,
+    tokenInfo: tokenInfoPda,
+    roleGrant: rolePda(Role.TREASURER, authority.publicKey),
+    lpMint,
+    liquidityLock: liquidityLockPda,
+    vault: vaultPda,
+    lpTokenProgram: TOKEN_PROGRAM_ID,
+  });
+
+  const extendLock = (unlockTs: number) =>
+    program.methods
+      .extendLiquidityLock(new anchor.BN(unlockTs))
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        roleGrant: rolePda(Role.TREASURER, authority.publicKey),
+        liquidityLock: liquidityLockPda,
+      })
+      .rpc();
+
+  const releaseLock = () =>
+    program.methods
+      .releaseLiquidity()
+      .accounts({ ...lockAccounts(), destination: lpSource })
+      .rpc();
+
+  const viewLock = () =>
+    program.methods
+      .getLiquidityLock()
+      .accounts({
+        mint: mintKeypair.publicKey,
+        liquidityLock: liquidityLockPda,
+        vault: vaultPda,
+      })
+      .view();
 
   before(async () => {
     // Derive PDA for token info
@@ -37,6 +205,12 @@ describe("dollar-token", () => {
       program.programId
     );
 
+    // Derive PDA that holds the SPL mint and freeze authority
+    [mintAuthorityPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("mint_authority"), mintKeypair.publicKey.toBuffer()],
+      program.programId
+    );
+
     // Get associated token account for authority
     authorityTokenAccount = await getAssociatedTokenAddress(
       mintKeypair.publicKey,
@@ -50,13 +224,17 @@ describe("dollar-token", () => {
         "Dollar Token",
         "TDL", 
         "https://raw.githubusercontent.com/example/dollar-token-metadata.json",
-        9
+        9,
+        MAX_SUPPLY
       )
       .accounts({
         authority: authority.publicKey,
         mint: mintKeypair.publicKey,
+        mintAuthority: mintAuthorityPda,
         tokenInfo: tokenInfoPda,
+        metadata: metadataPda(mintKeypair.publicKey),
         tokenProgram: TOKEN_PROGRAM_ID,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: SYSVAR_RENT_PUBKEY,
       })
@@ -71,7 +249,154 @@ describe("dollar-token", () => {
     assert.equal(tokenInfoAccount.symbol, "TDL");
     assert.equal(tokenInfoAccount.decimals, 9);
     assert.equal(tokenInfoAccount.totalSupply.toNumber(), 0);
+    assert.equal(tokenInfoAccount.maxSupply.toString(), MAX_SUPPLY.toString());
     assert.isTrue(tokenInfoAccount.isInitialized);
+
+    // Mint and freeze authority belong to the program, not the signer
+    const mintAccount = await getMint(provider.connection, mintKeypair.publicKey);
+    assert.equal(mintAccount.mintAuthority.toString(), mintAuthorityPda.toString());
+    assert.equal(mintAccount.freezeAuthority.toString(), mintAuthorityPda.toString());
+  });
+
+  const initializeWithDecimals = (testMint: Keypair, decimals: number) => {
+    const [testTokenInfo] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_info"), testMint.publicKey.toBuffer()],
+      program.programId
+    );
+    const [testMintAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_authority"), testMint.publicKey.toBuffer()],
+      program.programId
+    );
+    return program.methods
+      .initializeToken("Test Token", "TST", "https://example.com/test.json", decimals, MAX_SUPPLY)
+      .accounts({
+        authority: authority.publicKey,
+        mint: testMint.publicKey,
+        mintAuthority: testMintAuthority,
+        tokenInfo: testTokenInfo,
+        metadata: metadataPda(testMint.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([testMint])
+      .rpc();
+  };
+
+  it("Creates the mint with the requested decimals", async () => {
+    const testMint = Keypair.generate();
+    await initializeWithDecimals(testMint, 6);
+
+    const mintAccount = await getMint(provider.connection, testMint.publicKey);
+    assert.equal(mintAccount.decimals, 6);
+  });
+
+  it("Rejects decimals outside the supported range", async () => {
+    try {
+      await initializeWithDecimals(Keypair.generate(), 19);
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidDecimals");
+    }
+  });
+
+  it("Creates the Metaplex metadata account", async () => {
+    const metadataAccount = await provider.connection.getAccountInfo(
+      metadataPda(mintKeypair.publicKey)
+    );
+    assert.isNotNull(metadataAccount);
+    assert.equal(metadataAccount.owner.toString(), TOKEN_METADATA_PROGRAM_ID.toString());
+    assert.include(metadataAccount.data.toString(), "Dollar Token");
+  });
+
+  it("Updates metadata in TokenInfo and the metadata account", async () => {
+    const newUri = "https://example.com/dollar-token-v2.json";
+    await program.methods
+      .updateTokenMetadata("Dollar Token", "TDL", newUri)
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        mintAuthority: mintAuthorityPda,
+        metadata: metadataPda(mintKeypair.publicKey),
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      })
+      .rpc();
+
+    const tokenInfoAccount = await program.account.tokenInfo.fetch(tokenInfoPda);
+    assert.equal(tokenInfoAccount.uri, newUri);
+
+    const metadataAccount = await provider.connection.getAccountInfo(
+      metadataPda(mintKeypair.publicKey)
+    );
+    assert.include(metadataAccount.data.toString(), newUri);
+  });
+
+  it("Grant operational roles", async () => {
+    await grantRole(Role.MINTER, authority.publicKey);
+    await grantRole(Role.BURNER, authority.publicKey);
+    await grantRole(Role.TREASURER, authority.publicKey);
+
+    const minterGrant = await program.account.roleGrant.fetch(
+      rolePda(Role.MINTER, authority.publicKey)
+    );
+    assert.equal(minterGrant.holder.toString(), authority.publicKey.toString());
+    assert.isNull(minterGrant.expiresAt);
+  });
+
+  it("Rejects bucket caps beyond the max supply", async () => {
+    try {
+      await program.methods
+        .createAllocationBucket(BUCKET_ARGS[Bucket.PRESALE] as any, MAX_SUPPLY.addn(1), authorityTokenAccount)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.MINTER, authority.publicKey),
+          bucket: bucketPda(Bucket.PRESALE),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "BucketCapsExceedMaxSupply");
+    }
+  });
+
+  it("Creates buckets matching the published distribution", async () => {
+    // Presale 25%, Liquidity 20%, Rewards 25%, Team 15%, Marketing 15%
+    const shares = [25, 20, 25, 15, 15];
+    for (let category = 0; category < shares.length; category++) {
+      const destination =
+        category === Bucket.PRESALE ? authorityTokenAccount : Keypair.generate().publicKey;
+      await openBucket(
+        category,
+        MAX_SUPPLY.muln(shares[category]).divn(100),
+        destination,
+        category === Bucket.PRESALE ? new anchor.BN(100_000_000_000) : new anchor.BN(0)
+      );
+    }
+
+    const buckets = await program.methods
+      .getAllocationBuckets()
+      .accounts({ mint: mintKeypair.publicKey })
+      .remainingAccounts(
+        shares.map((_, category) => ({
+          pubkey: bucketPda(category),
+          isSigner: false,
+          isWritable: false,
+        }))
+      )
+      .view();
+    assert.equal(buckets.length, shares.length);
+    buckets.forEach((bucket, category) => {
+      assert.equal(bucket.cap.toString(), MAX_SUPPLY.muln(shares[category]).divn(100).toString());
+      assert.equal(bucket.minted.toNumber(), 0);
+    });
+
+    const tokenInfoAccount = await program.account.tokenInfo.fetch(tokenInfoPda);
+    assert.equal(tokenInfoAccount.bucketCapsTotal.toString(), MAX_SUPPLY.toString());
   });
 
   it("Mint Tokens", async () => {
@@ -94,8 +419,12 @@ describe("dollar-token", () => {
       .accounts({
         authority: authority.publicKey,
         mint: mintKeypair.publicKey,
+        mintAuthority: mintAuthorityPda,
         tokenInfo: tokenInfoPda,
+        roleGrant: rolePda(Role.MINTER, authority.publicKey),
+        bucket: bucketPda(Bucket.PRESALE),
         destination: authorityTokenAccount,
+        destinationWhitelist: whitelistPda(authority.publicKey),
         tokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
@@ -107,6 +436,31 @@ describe("dollar-token", () => {
     assert.equal(tokenInfoAccount.totalSupply.toNumber(), mintAmount.toNumber());
   });
 
+  it("Cannot mint beyond a bucket's released amount", async () => {
+    const bucket = await program.account.allocationBucket.fetch(bucketPda(Bucket.PRESALE));
+    assert.equal(bucket.minted.toString(), bucket.released.toString());
+
+    try {
+      await program.methods
+        .mintTokens(new anchor.BN(1))
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          mintAuthority: mintAuthorityPda,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.MINTER, authority.publicKey),
+          bucket: bucketPda(Bucket.PRESALE),
+          destination: authorityTokenAccount,
+          destinationWhitelist: whitelistPda(authority.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "BucketReleaseExceeded");
+    }
+  });
+
   it("Burn Tokens", async () => {
     const burnAmount = new anchor.BN(10_000_000_000); // 10 tokens
     
@@ -116,6 +470,7 @@ describe("dollar-token", () => {
         authority: authority.publicKey,
         mint: mintKeypair.publicKey,
         tokenInfo: tokenInfoPda,
+        roleGrant: rolePda(Role.BURNER, authority.publicKey),
         from: authorityTokenAccount,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
@@ -141,8 +496,12 @@ describe("dollar-token", () => {
         .accounts({
           authority: unauthorizedKeypair.publicKey,
           mint: mintKeypair.publicKey,
+          mintAuthority: mintAuthorityPda,
           tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.MINTER, unauthorizedKeypair.publicKey),
+          bucket: bucketPda(Bucket.PRESALE),
           destination: authorityTokenAccount,
+          destinationWhitelist: whitelistPda(authority.publicKey),
           tokenProgram: TOKEN_PROGRAM_ID,
         })
         .signers([unauthorizedKeypair])
@@ -150,15 +509,251 @@ describe("dollar-token", () => {
       
       assert.fail("Expected transaction to fail");
     } catch (error) {
-      assert.include(error.toString(), "UnauthorizedAccess");
+      // No minter grant exists for this signer
+      assert.include(error.toString(), "AccountNotInitialized");
+    }
+  });
+
+  it("Cannot mint past the max supply", async () => {
+    try {
+      await program.methods
+        .mintTokens(MAX_SUPPLY)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          mintAuthority: mintAuthorityPda,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.MINTER, authority.publicKey),
+          bucket: bucketPda(Bucket.PRESALE),
+          destination: authorityTokenAccount,
+          destinationWhitelist: whitelistPda(authority.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "MaxSupplyExceeded");
+    }
+  });
+
+  it("Renounce minting", async () => {
+    await program.methods
+      .renounceMinting()
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        mintAuthority: mintAuthorityPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const tokenInfoAccount = await program.account.tokenInfo.fetch(tokenInfoPda);
+    assert.isTrue(tokenInfoAccount.mintingRenounced);
+
+    const mintAccount = await getMint(provider.connection, mintKeypair.publicKey);
+    assert.isNull(mintAccount.mintAuthority);
+
+    try {
+      await program.methods
+        .mintTokens(new anchor.BN(1))
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          mintAuthority: mintAuthorityPda,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.MINTER, authority.publicKey),
+          bucket: bucketPda(Bucket.PRESALE),
+          destination: authorityTokenAccount,
+          destinationWhitelist: whitelistPda(authority.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "MintingRenounced");
+    }
+  });
+
+  it("Rejects an unlock time in the past", async () => {
+    // Any SPL mint stands in for the pool's LP token
+    lpMint = await createMint(provider.connection, authority.payer, authority.publicKey, null, 6);
+    lpSource = await createAssociatedTokenAccount(
+      provider.connection,
+      authority.payer,
+      lpMint,
+      authority.publicKey
+    );
+    await mintTo(provider.connection, authority.payer, lpMint, lpSource, authority.payer, LP_LOCKED);
+
+    [liquidityLockPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("liquidity_lock"), mintKeypair.publicKey.toBuffer(), lpMint.toBuffer()],
+      program.programId
+    );
+    [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("liquidity_lock_vault"), liquidityLockPda.toBuffer()],
+      program.programId
+    );
+
+    try {
+      await program.methods
+        .createLiquidityLock(new anchor.BN(now() - 60))
+        .accounts({ ...lockAccounts(), systemProgram: SystemProgram.programId })
+        .rpc();
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidUnlockTime");
+    }
+  });
+
+  it("Rejects a lock from a wallet without the Treasurer role", async () => {
+    const outsider = Keypair.generate();
+    try {
+      await program.methods
+        .createLiquidityLock(new anchor.BN(now() + 60))
+        .accounts({
+          ...lockAccounts(),
+          authority: outsider.publicKey,
+          roleGrant: rolePda(Role.TREASURER, outsider.publicKey),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([outsider])
+        .rpc();
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      // No treasurer grant exists for this signer
+      assert.include(error.toString(), "AccountNotInitialized");
+    }
+  });
+
+  it("Locks LP tokens until the unlock time", async () => {
+    const unlockTs = now() + 3;
+    await program.methods
+      .createLiquidityLock(new anchor.BN(unlockTs))
+      .accounts({ ...lockAccounts(), systemProgram: SystemProgram.programId })
+      .rpc();
+    await program.methods
+      .depositLiquidity(new anchor.BN(LP_LOCKED))
+      .accounts({ ...lockAccounts(), source: lpSource })
+      .rpc();
+
+    const summary = await viewLock();
+    assert.equal(summary.amount.toNumber(), LP_LOCKED);
+    assert.equal(summary.deposited.toNumber(), LP_LOCKED);
+    assert.equal(summary.unlockTs.toNumber(), unlockTs);
+    assert.isFalse(summary.unlocked);
+    assert.isFalse(summary.released);
+
+    try {
+      await releaseLock();
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "LiquidityStillLocked");
+    }
+  });
+
+  it("Only extends the unlock time", async () => {
+    const lock = await program.account.liquidityLock.fetch(liquidityLockPda);
+    try {
+      await extendLock(lock.unlockTs.toNumber() - 1);
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "UnlockTimeDecrease");
+    }
+
+    await extendLock(lock.unlockTs.toNumber() + 2);
+    const extended = await program.account.liquidityLock.fetch(liquidityLockPda);
+    assert.equal(extended.unlockTs.toNumber(), lock.unlockTs.toNumber() + 2);
+  });
+
+  it("Releases the vault once unlocked", async () => {
+    await sleep(6000);
+    await releaseLock();
+
+    assert.equal(Number((await getAccount(provider.connection, lpSource)).amount), LP_LOCKED);
+    const summary = await viewLock();
+    assert.equal(summary.amount.toNumber(), 0);
+    assert.isTrue(summary.released);
+
+    try {
+      await releaseLock();
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "LiquidityAlreadyReleased");
+    }
+  });
+
+  it("Propose and cancel authority handover", async () => {
+    const proposedKeypair = Keypair.generate();
+
+    await program.methods
+      .proposeAuthority(proposedKeypair.publicKey)
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+      })
+      .rpc();
+
+    let tokenInfoAccount = await program.account.tokenInfo.fetch(tokenInfoPda);
+    assert.equal(
+      tokenInfoAccount.pendingAuthority.toString(),
+      proposedKeypair.publicKey.toString()
+    );
+
+    await program.methods
+      .cancelAuthorityProposal()
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+      })
+      .rpc();
+
+    tokenInfoAccount = await program.account.tokenInfo.fetch(tokenInfoPda);
+    assert.isNull(tokenInfoAccount.pendingAuthority);
+    assert.equal(
+      tokenInfoAccount.authority.toString(),
+      authority.publicKey.toString()
+    );
+  });
+
+  it("Cannot accept authority without a matching proposal", async () => {
+    const impostor = Keypair.generate();
+
+    await program.methods
+      .proposeAuthority(Keypair.generate().publicKey)
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+      })
+      .rpc();
+
+    try {
+      await program.methods
+        .acceptAuthority()
+        .accounts({
+          newAuthority: impostor.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+        })
+        .signers([impostor])
+        .rpc();
+
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidPendingAuthority");
     }
   });
 
   it("Transfer Authority", async () => {
     const newAuthorityKeypair = Keypair.generate();
     
-    const tx = await program.methods
-      .transferAuthority(newAuthorityKeypair.publicKey)
+    const proposeTx = await program.methods
+      .proposeAuthority(newAuthorityKeypair.publicKey)
       .accounts({
         authority: authority.publicKey,
         mint: mintKeypair.publicKey,
@@ -166,13 +761,33 @@ describe("dollar-token", () => {
       })
       .rpc();
 
-    console.log("Transfer authority transaction signature", tx);
+    console.log("Propose authority transaction signature", proposeTx);
+
+    // Authority does not move until the new key accepts
+    let tokenInfoAccount = await program.account.tokenInfo.fetch(tokenInfoPda);
+    assert.equal(
+      tokenInfoAccount.authority.toString(), 
+      authority.publicKey.toString()
+    );
+
+    const acceptTx = await program.methods
+      .acceptAuthority()
+      .accounts({
+        newAuthority: newAuthorityKeypair.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+      })
+      .signers([newAuthorityKeypair])
+      .rpc();
+
+    console.log("Accept authority transaction signature", acceptTx);
 
     // Verify authority changed
-    const tokenInfoAccount = await program.account.tokenInfo.fetch(tokenInfoPda);
+    tokenInfoAccount = await program.account.tokenInfo.fetch(tokenInfoPda);
     assert.equal(
       tokenInfoAccount.authority.toString(), 
       newAuthorityKeypair.publicKey.toString()
     );
+    assert.isNull(tokenInfoAccount.pendingAuthority);
   });
 });
@@ -0,0 +1,207 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { DollarToken } from "../target/types/dollar_token";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, getAccount, getMint } from "@solana/spl-token";
+import { assert } from "chai";
+import legacyAuthoritySecret from "./fixtures/legacy-authority.json";
+
+// Loaded by the test validator from tests/fixtures (see Anchor.toml): a mint
+// with 9 decimals whose mint and freeze authority is the fixture keypair, a
+// TokenInfo in the original layout that recorded 6 decimals and no cap, and a
+// token account the old freeze authority left frozen.
+const LEGACY_MINT = new PublicKey("5f6k3vJ8SxRG5mbC1enjaVukjy9ELP7ENt22KiEja7o4");
+const LEGACY_FROZEN_ACCOUNT = new PublicKey("7owJgSGNouFJrpFs4i3fjB7VrUh5sd4RUgw8Nha7LJDJ");
+const LEGACY_SUPPLY = new anchor.BN("1000000000000000");
+const LEGACY_SPACE = 339;
+
+const MAX_SUPPLY = new anchor.BN("100000000000000000");
+const DEFAULT_APPEAL_WINDOW = 14 * 24 * 60 * 60;
+const MIN_DRAIN_LIQUIDITY_DELAY = 24 * 60 * 60;
+// Role discriminant of Pauser, also its byte in grant PDA seeds
+const PAUSER = 3;
+const SUSPECTED_COMPROMISE = 1;
+
+describe("Mint Authority Migration", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.DollarToken as Program<DollarToken>;
+  const authority = Keypair.fromSecretKey(Uint8Array.from(legacyAuthoritySecret));
+
+  const [tokenInfoPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("token_info"), LEGACY_MINT.toBuffer()],
+    program.programId
+  );
+  const [mintAuthorityPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("mint_authority"), LEGACY_MINT.toBuffer()],
+    program.programId
+  );
+
+  const [pauserGrantPda] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("role"),
+      LEGACY_MINT.toBuffer(),
+      Buffer.from([PAUSER]),
+      authority.publicKey.toBuffer(),
+    ],
+    program.programId
+  );
+
+  const frozen = (account: PublicKey) => ({
+    pubkey: account,
+    isSigner: false,
+    isWritable: false,
+  });
+
+  const migrate = (maxSupply: anchor.BN, frozenAccounts = [LEGACY_FROZEN_ACCOUNT]) =>
+    program.methods
+      .migrateMintAuthority(maxSupply)
+      .accounts({
+        authority: authority.publicKey,
+        currentMintAuthority: authority.publicKey,
+        mint: LEGACY_MINT,
+        tokenInfo: tokenInfoPda,
+        mintAuthority: mintAuthorityPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(frozenAccounts.map(frozen))
+      .signers([authority])
+      .rpc();
+
+  before(async () => {
+    const sig = await provider.connection.requestAirdrop(
+      authority.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(sig);
+  });
+
+  it("Starts from a TokenInfo in the original layout", async () => {
+    const account = await provider.connection.getAccountInfo(tokenInfoPda);
+    assert.equal(account.data.length, LEGACY_SPACE);
+    assert.ok(account.owner.equals(program.programId));
+  });
+
+  it("Rejects a max supply below the minted supply", async () => {
+    try {
+      await migrate(LEGACY_SUPPLY.subn(1));
+      assert.fail("Should have thrown error");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidMaxSupply");
+    }
+  });
+
+  it("Rejects a signer other than the recorded authority", async () => {
+    const stranger = Keypair.generate();
+    try {
+      await program.methods
+        .migrateMintAuthority(MAX_SUPPLY)
+        .accounts({
+          authority: stranger.publicKey,
+          currentMintAuthority: authority.publicKey,
+          mint: LEGACY_MINT,
+          tokenInfo: tokenInfoPda,
+          mintAuthority: mintAuthorityPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([stranger, authority])
+        .rpc();
+      assert.fail("Should have thrown error");
+    } catch (error) {
+      assert.include(error.toString(), "UnauthorizedAccess");
+    }
+  });
+
+  it("Rejects a frozen account listed twice", async () => {
+    try {
+      await migrate(MAX_SUPPLY, [LEGACY_FROZEN_ACCOUNT, LEGACY_FROZEN_ACCOUNT]);
+      assert.fail("Should have thrown error");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidFrozenAccount");
+    }
+  });
+
+  it("Moves the mint authority to the PDA and rewrites TokenInfo", async () => {
+    await migrate(MAX_SUPPLY);
+
+    const mint = await getMint(provider.connection, LEGACY_MINT);
+    assert.ok(mint.mintAuthority.equals(mintAuthorityPda));
+    assert.ok(mint.freezeAuthority.equals(mintAuthorityPda));
+
+    const account = await provider.connection.getAccountInfo(tokenInfoPda);
+    assert.isAbove(account.data.length, LEGACY_SPACE);
+
+    const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+    assert.ok(tokenInfo.authority.equals(authority.publicKey));
+    assert.ok(tokenInfo.mint.equals(LEGACY_MINT));
+    assert.equal(tokenInfo.name, "Legacy Dollar");
+    assert.equal(tokenInfo.symbol, "LUSD");
+    assert.equal(tokenInfo.uri, "https://example.com/legacy.json");
+    assert.equal(tokenInfo.decimals, 9);
+    assert.equal(tokenInfo.totalSupply.toString(), LEGACY_SUPPLY.toString());
+    assert.isTrue(tokenInfo.isInitialized);
+    assert.isFalse(tokenInfo.whitelistEnabled);
+    assert.isTrue(tokenInfo.tradingEnabled);
+    assert.isTrue(tokenInfo.blacklistEnabled);
+    assert.isNull(tokenInfo.pendingAuthority);
+    assert.equal(tokenInfo.maxSupply.toString(), MAX_SUPPLY.toString());
+    assert.isFalse(tokenInfo.mintingRenounced);
+    assert.equal(tokenInfo.appealWindow.toNumber(), DEFAULT_APPEAL_WINDOW);
+    assert.equal(
+      tokenInfo.timelockDelays.drainLiquidity.toNumber(),
+      MIN_DRAIN_LIQUIDITY_DELAY
+    );
+    assert.ok(tokenInfo.treasury.equals(PublicKey.default));
+    // Counted from the frozen accounts passed to the migration
+    assert.equal(tokenInfo.frozenAccounts.toNumber(), 1);
+  });
+
+  it("Thaws an account frozen before the migration", async () => {
+    await program.methods
+      .grantRole({ pauser: {} } as any, authority.publicKey, null)
+      .accounts({
+        admin: authority.publicKey,
+        mint: LEGACY_MINT,
+        tokenInfo: tokenInfoPda,
+        adminGrant: null,
+        roleGrant: pauserGrantPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    const thaw = () =>
+      program.methods
+        .thawAccount(SUSPECTED_COMPROMISE)
+        .accounts({
+          authority: authority.publicKey,
+          mint: LEGACY_MINT,
+          tokenInfo: tokenInfoPda,
+          roleGrant: pauserGrantPda,
+          mintAuthority: mintAuthorityPda,
+          account: LEGACY_FROZEN_ACCOUNT,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc();
+
+    assert.isTrue((await getAccount(provider.connection, LEGACY_FROZEN_ACCOUNT)).isFrozen);
+    await thaw();
+
+    assert.isFalse((await getAccount(provider.connection, LEGACY_FROZEN_ACCOUNT)).isFrozen);
+    const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+    assert.equal(tokenInfo.frozenAccounts.toNumber(), 0);
+  });
+
+  it("Rejects migrating the same token twice", async () => {
+    try {
+      await migrate(MAX_SUPPLY);
+      assert.fail("Should have thrown error");
+    } catch (error) {
+      assert.include(error.toString(), "AlreadyMigrated");
+    }
+  });
+});
@@ -0,0 +1,448 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TdlStaking } from "../target/types/tdl_staking";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  LAMPORTS_PER_SOL,
+  Transaction,
+} from "@solana/web3.js";
+import {
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMint,
+  getMintLen,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+// 1% withheld by the transfer fee extension, like the Token-2022 TDL mint
+const TRANSFER_FEE_BPS = 100;
+
+describe("Staking", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.TdlStaking as Program<TdlStaking>;
+  const admin = provider.wallet as anchor.Wallet;
+
+  const REWARD_RATE = new anchor.BN(1_000);
+  const LOCKUP_SECONDS = 2;
+  const STAKE_AMOUNT = new anchor.BN(1_000_000);
+
+  let mint: PublicKey;
+  let poolPda: PublicKey;
+  let stakeVaultPda: PublicKey;
+  let rewardVaultPda: PublicKey;
+
+  const positionPda = (owner: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("stake"), poolPda.toBuffer(), owner.toBuffer()],
+      program.programId
+    )[0];
+
+  const tokenBalance = async (account: PublicKey) =>
+    Number((await getAccount(provider.connection, account)).amount);
+
+  const fundedStaker = async () => {
+    const owner = Keypair.generate();
+    const sig = await provider.connection.requestAirdrop(owner.publicKey, LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(sig);
+    const tokenAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        admin.payer,
+        mint,
+        owner.publicKey
+      )
+    ).address;
+    await mintTo(
+      provider.connection,
+      admin.payer,
+      mint,
+      tokenAccount,
+      admin.payer,
+      STAKE_AMOUNT.toNumber()
+    );
+    return { owner, tokenAccount };
+  };
+
+  const stake = (owner: Keypair, ownerTokenAccount: PublicKey, amount: anchor.BN) =>
+    program.methods
+      .stake(amount)
+      .accounts({
+        owner: owner.publicKey,
+        mint,
+        pool: poolPda,
+        position: positionPda(owner.publicKey),
+        stakeVault: stakeVaultPda,
+        rewardVault: rewardVaultPda,
+        ownerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+  const unstake = (owner: Keypair, ownerTokenAccount: PublicKey, amount: anchor.BN) =>
+    program.methods
+      .unstake(amount)
+      .accounts({
+        owner: owner.publicKey,
+        mint,
+        pool: poolPda,
+        position: positionPda(owner.publicKey),
+        stakeVault: stakeVaultPda,
+        rewardVault: rewardVaultPda,
+        ownerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+  const claim = (owner: Keypair, ownerTokenAccount: PublicKey) =>
+    program.methods
+      .claim()
+      .accounts({
+        owner: owner.publicKey,
+        mint,
+        pool: poolPda,
+        position: positionPda(owner.publicKey),
+        rewardVault: rewardVaultPda,
+        ownerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+  const setRewardRate = (rate: anchor.BN) =>
+    program.methods
+      .setRewardRate(rate)
+      .accounts({ admin: admin.publicKey, pool: poolPda, rewardVault: rewardVaultPda })
+      .rpc();
+
+  before(async () => {
+    mint = await createMint(provider.connection, admin.payer, admin.publicKey, null, 9);
+    [poolPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), mint.toBuffer(), admin.publicKey.toBuffer()],
+      program.programId
+    );
+    [stakeVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("stake_vault"), poolPda.toBuffer()],
+      program.programId
+    );
+    [rewardVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_vault"), poolPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .initializePool(REWARD_RATE, new anchor.BN(LOCKUP_SECONDS))
+      .accounts({
+        admin: admin.publicKey,
+        mint,
+        pool: poolPda,
+        stakeVault: stakeVaultPda,
+        rewardVault: rewardVaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Stands in for minting the Rewards bucket into the reward vault
+    await mintTo(provider.connection, admin.payer, mint, rewardVaultPda, admin.payer, 1_000_000_000);
+  });
+
+  it("Should initialize the pool", async () => {
+    const pool = await program.account.stakingPool.fetch(poolPda);
+    assert.ok(pool.admin.equals(admin.publicKey));
+    assert.ok(pool.rewardVault.equals(rewardVaultPda));
+    assert.ok(pool.rewardRate.eq(REWARD_RATE));
+    assert.equal(pool.totalStaked.toNumber(), 0);
+  });
+
+  it("Should give another wallet its own pool instead of the admin's", async () => {
+    const { owner: squatter } = await fundedStaker();
+    const [squatterPool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), mint.toBuffer(), squatter.publicKey.toBuffer()],
+      program.programId
+    );
+    const vaultPda = (seed: string) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from(seed), squatterPool.toBuffer()],
+        program.programId
+      )[0];
+
+    await program.methods
+      .initializePool(new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        admin: squatter.publicKey,
+        mint,
+        pool: squatterPool,
+        stakeVault: vaultPda("stake_vault"),
+        rewardVault: vaultPda("reward_vault"),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([squatter])
+      .rpc();
+
+    assert.notOk(squatterPool.equals(poolPda));
+    const pool = await program.account.stakingPool.fetch(poolPda);
+    assert.ok(pool.admin.equals(admin.publicKey));
+  });
+
+  it("Should stake and hold the principal in the vault", async () => {
+    const { owner, tokenAccount } = await fundedStaker();
+    await stake(owner, tokenAccount, STAKE_AMOUNT);
+
+    const position = await program.account.stakePosition.fetch(positionPda(owner.publicKey));
+    assert.ok(position.amount.eq(STAKE_AMOUNT));
+    assert.equal(await tokenBalance(tokenAccount), 0);
+    assert.isAtLeast(await tokenBalance(stakeVaultPda), STAKE_AMOUNT.toNumber());
+
+    try {
+      await unstake(owner, tokenAccount, STAKE_AMOUNT);
+      assert.fail("Should have thrown an error");
+    } catch (error) {
+      assert.include(error.message, "StillLocked");
+    }
+
+    await sleep((LOCKUP_SECONDS + 1) * 1000);
+    await unstake(owner, tokenAccount, STAKE_AMOUNT);
+    assert.equal(await tokenBalance(tokenAccount), STAKE_AMOUNT.toNumber());
+  });
+
+  it("Should accrue rewards per second while staked", async () => {
+    const { owner, tokenAccount } = await fundedStaker();
+    await stake(owner, tokenAccount, STAKE_AMOUNT);
+
+    await sleep(3000);
+    await claim(owner, tokenAccount);
+
+    const claimed = await tokenBalance(tokenAccount);
+    assert.isAbove(claimed, 0);
+    const position = await program.account.stakePosition.fetch(positionPda(owner.publicKey));
+    assert.equal(position.rewardsOwed.toNumber(), 0);
+  });
+
+  it("Should not accrue rewards the reward vault cannot pay", async () => {
+    const { owner: poolAdmin, tokenAccount } = await fundedStaker();
+    const [unfundedPool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), mint.toBuffer(), poolAdmin.publicKey.toBuffer()],
+      program.programId
+    );
+    const vaultPda = (seed: string) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from(seed), unfundedPool.toBuffer()],
+        program.programId
+      )[0];
+    await program.methods
+      .initializePool(REWARD_RATE, new anchor.BN(0))
+      .accounts({
+        admin: poolAdmin.publicKey,
+        mint,
+        pool: unfundedPool,
+        stakeVault: vaultPda("stake_vault"),
+        rewardVault: vaultPda("reward_vault"),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([poolAdmin])
+      .rpc();
+
+    const accounts = {
+      owner: poolAdmin.publicKey,
+      mint,
+      pool: unfundedPool,
+      position: PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), unfundedPool.toBuffer(), poolAdmin.publicKey.toBuffer()],
+        program.programId
+      )[0],
+      rewardVault: vaultPda("reward_vault"),
+      ownerTokenAccount: tokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    await program.methods
+      .stake(STAKE_AMOUNT)
+      .accounts({
+        ...accounts,
+        stakeVault: vaultPda("stake_vault"),
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([poolAdmin])
+      .rpc();
+
+    await sleep(2000);
+    try {
+      await program.methods.claim().accounts(accounts).signers([poolAdmin]).rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error) {
+      assert.include(error.message, "NothingToClaim");
+    }
+    const pool = await program.account.stakingPool.fetch(unfundedPool);
+    assert.equal(pool.rewardPerTokenStored.toString(), "0");
+    assert.equal(pool.reservedRewards.toNumber(), 0);
+  });
+
+  it("Should keep accrued rewards when the rate is cut", async () => {
+    const { owner, tokenAccount } = await fundedStaker();
+    await stake(owner, tokenAccount, STAKE_AMOUNT);
+
+    await sleep(2000);
+    await setRewardRate(new anchor.BN(0));
+    const pool = await program.account.stakingPool.fetch(poolPda);
+    assert.equal(pool.rewardRate.toNumber(), 0);
+
+    // Everything earned before the cut is still claimable
+    await claim(owner, tokenAccount);
+    assert.isAbove(await tokenBalance(tokenAccount), 0);
+
+    try {
+      await claim(owner, tokenAccount);
+      assert.fail("Should have thrown an error");
+    } catch (error) {
+      assert.include(error.message, "NothingToClaim");
+    }
+
+    await setRewardRate(REWARD_RATE);
+  });
+
+  it("Should only let the admin change the rate", async () => {
+    const attacker = Keypair.generate();
+    try {
+      await program.methods
+        .setRewardRate(new anchor.BN(1_000_000))
+        .accounts({ admin: attacker.publicKey, pool: poolPda, rewardVault: rewardVaultPda })
+        .signers([attacker])
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error) {
+      assert.include(error.message, "ConstraintHasOne");
+    }
+  });
+
+  it("Should credit only what reaches the vault on a fee-bearing mint", async () => {
+    const feeMint = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: admin.publicKey,
+          newAccountPubkey: feeMint.publicKey,
+          space: mintLen,
+          lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          feeMint.publicKey,
+          admin.publicKey,
+          admin.publicKey,
+          TRANSFER_FEE_BPS,
+          BigInt(STAKE_AMOUNT.toString()),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(
+          feeMint.publicKey,
+          9,
+          admin.publicKey,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ),
+      [feeMint]
+    );
+
+    const [feePool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), feeMint.publicKey.toBuffer(), admin.publicKey.toBuffer()],
+      program.programId
+    );
+    const vaultPda = (seed: string) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from(seed), feePool.toBuffer()],
+        program.programId
+      )[0];
+    await program.methods
+      .initializePool(new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        admin: admin.publicKey,
+        mint: feeMint.publicKey,
+        pool: feePool,
+        stakeVault: vaultPda("stake_vault"),
+        rewardVault: vaultPda("reward_vault"),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const owner = Keypair.generate();
+    const sig = await provider.connection.requestAirdrop(owner.publicKey, LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(sig);
+    const ownerTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        admin.payer,
+        feeMint.publicKey,
+        owner.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).address;
+    await mintTo(
+      provider.connection,
+      admin.payer,
+      feeMint.publicKey,
+      ownerTokenAccount,
+      admin.payer,
+      STAKE_AMOUNT.toNumber(),
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const accounts = {
+      owner: owner.publicKey,
+      mint: feeMint.publicKey,
+      pool: feePool,
+      position: PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), feePool.toBuffer(), owner.publicKey.toBuffer()],
+        program.programId
+      )[0],
+      stakeVault: vaultPda("stake_vault"),
+      rewardVault: vaultPda("reward_vault"),
+      ownerTokenAccount,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    };
+    await program.methods
+      .stake(STAKE_AMOUNT)
+      .accounts({ ...accounts, systemProgram: SystemProgram.programId })
+      .signers([owner])
+      .rpc();
+
+    const received = STAKE_AMOUNT.muln(10_000 - TRANSFER_FEE_BPS).divn(10_000);
+    const position = await program.account.stakePosition.fetch(accounts.position);
+    assert.equal(position.amount.toString(), received.toString());
+    const pool = await program.account.stakingPool.fetch(feePool);
+    assert.equal(pool.totalStaked.toString(), received.toString());
+
+    // The whole credited stake can leave the vault again
+    await program.methods.unstake(received).accounts(accounts).signers([owner]).rpc();
+    const vault = await getAccount(
+      provider.connection,
+      vaultPda("stake_vault"),
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(vault.amount, BigInt(0));
+  });
+});
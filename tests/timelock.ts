@@ -0,0 +1,530 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { DollarToken } from "../target/types/dollar_token";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, createAssociatedTokenAccount, getAccount } from "@solana/spl-token";
+import { assert } from "chai";
+
+// 100,000,000 TDL with 9 decimals
+const MAX_SUPPLY = new anchor.BN("100000000000000000");
+
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
+const metadataPda = (mint: PublicKey) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+
+// Role discriminants double as the role byte in grant PDA seeds
+const Role = {
+  MINTER: 0,
+  BURNER: 1,
+  COMPLIANCE_OFFICER: 2,
+  PAUSER: 3,
+  TREASURER: 4,
+  ROLE_ADMIN: 5,
+};
+const ROLE_ARGS = [
+  { minter: {} },
+  { burner: {} },
+  { complianceOfficer: {} },
+  { pauser: {} },
+  { treasurer: {} },
+  { roleAdmin: {} },
+];
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe("Timelock", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.DollarToken as Program<DollarToken>;
+  const authority = provider.wallet as anchor.Wallet;
+  const mintKeypair = Keypair.generate();
+
+  let tokenInfoPda: PublicKey;
+  let mintAuthorityPda: PublicKey;
+  let timelockAuthorityPda: PublicKey;
+
+  const rolePda = (role: number, holder: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("role"),
+        mintKeypair.publicKey.toBuffer(),
+        Buffer.from([role]),
+        holder.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  const grantRole = (role: number, holder: PublicKey) =>
+    program.methods
+      .grantRole(ROLE_ARGS[role] as any, holder, null)
+      .accounts({
+        admin: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        adminGrant: null,
+        roleGrant: rolePda(role, holder),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  const queuedActionPda = (index: number) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("queued_action"),
+        mintKeypair.publicKey.toBuffer(),
+        new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+  const treasuryPoolPda = (pool: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury_pool"), mintKeypair.publicKey.toBuffer(), pool.toBuffer()],
+      program.programId
+    )[0];
+
+  const NO_DRAIN_ACCOUNTS = { liquidityPool: null, treasury: null, treasuryPool: null };
+
+  const queue = (
+    index: number,
+    action: any,
+    roleGrant: PublicKey | null,
+    drainAccounts: {
+      liquidityPool: PublicKey | null;
+      treasury: PublicKey | null;
+      treasuryPool: PublicKey | null;
+    } = NO_DRAIN_ACCOUNTS
+  ) =>
+    program.methods
+      .queueAction(action)
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        queuedAction: queuedActionPda(index),
+        roleGrant,
+        ...drainAccounts,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  const execute = (index: number) =>
+    program.methods
+      .executeQueuedAction()
+      .accounts({
+        executor: authority.publicKey,
+        queuedBy: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        queuedAction: queuedActionPda(index),
+        mintAuthority: mintAuthorityPda,
+        timelockAuthority: timelockAuthorityPda,
+        destination: null,
+        destinationWhitelist: null,
+        bucket: null,
+        liquidityPool: null,
+        treasury: null,
+        treasuryPool: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+  before(async () => {
+    [tokenInfoPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_info"), mintKeypair.publicKey.toBuffer()],
+      program.programId
+    );
+    [mintAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_authority"), mintKeypair.publicKey.toBuffer()],
+      program.programId
+    );
+    [timelockAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("timelock_authority"), mintKeypair.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .initializeToken("Dollar Token", "TDL", "https://example.com/metadata.json", 9, MAX_SUPPLY)
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        mintAuthority: mintAuthorityPda,
+        tokenInfo: tokenInfoPda,
+        metadata: metadataPda(mintKeypair.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([mintKeypair])
+      .rpc();
+
+    await grantRole(Role.PAUSER, authority.publicKey);
+  });
+
+  it("Raises the trading delay directly", async () => {
+    await program.methods
+      .increaseTimelockDelay({ toggleTrading: {} }, new anchor.BN(2))
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+      })
+      .rpc();
+
+    const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+    assert.equal(tokenInfo.timelockDelays.toggleTrading.toNumber(), 2);
+  });
+
+  it("Rejects lowering a delay without the queue", async () => {
+    try {
+      await program.methods
+        .increaseTimelockDelay({ toggleTrading: {} }, new anchor.BN(0))
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+        })
+        .rpc();
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "TimelockDecreaseRequiresQueue");
+    }
+  });
+
+  it("Blocks the direct instruction once a delay is set", async () => {
+    try {
+      await program.methods
+        .toggleTrading(false)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.PAUSER, authority.publicKey),
+        })
+        .rpc();
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "TimelockRequired");
+    }
+  });
+
+  it("Executes a queued action only after its eta", async () => {
+    await queue(
+      0,
+      { toggleTrading: { enabled: false } },
+      rolePda(Role.PAUSER, authority.publicKey)
+    );
+
+    const queued = await program.account.queuedAction.fetch(queuedActionPda(0));
+    assert.equal(queued.eta.toNumber() - queued.queuedAt.toNumber(), 2);
+
+    try {
+      await execute(0);
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "TimelockNotElapsed");
+    }
+
+    await sleep(3000);
+    await execute(0);
+
+    const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+    assert.isFalse(tokenInfo.tradingEnabled);
+    assert.isNull(await provider.connection.getAccountInfo(queuedActionPda(0)));
+  });
+
+  it("Lets the authority cancel a queued action", async () => {
+    await queue(
+      1,
+      { toggleTrading: { enabled: true } },
+      rolePda(Role.PAUSER, authority.publicKey)
+    );
+
+    await program.methods
+      .cancelQueuedAction()
+      .accounts({
+        authority: authority.publicKey,
+        queuedBy: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        queuedAction: queuedActionPda(1),
+      })
+      .rpc();
+
+    assert.isNull(await provider.connection.getAccountInfo(queuedActionPda(1)));
+    const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+    assert.isFalse(tokenInfo.tradingEnabled);
+  });
+
+  it("Lowers a delay through the queue", async () => {
+    await queue(
+      2,
+      { setTimelockDelay: { kind: { toggleTrading: {} }, delay: new anchor.BN(0) } },
+      null
+    );
+    await sleep(3000);
+    await execute(2);
+
+    const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+    assert.equal(tokenInfo.timelockDelays.toggleTrading.toNumber(), 0);
+  });
+
+  describe("Treasury drains", () => {
+    const DRAIN_DELAY = 24 * 60 * 60;
+    const EPOCH_CAP = new anchor.BN(1_000_000);
+    const treasuryOwner = Keypair.generate();
+    let liquidityPool: PublicKey;
+    let treasury: PublicKey;
+
+    const drain = (amount: anchor.BN, to: PublicKey) => ({
+      drainLiquidity: { amount, liquidityPool, treasury: to },
+    });
+
+    before(async () => {
+      liquidityPool = await createAssociatedTokenAccount(
+        provider.connection,
+        authority.payer,
+        mintKeypair.publicKey,
+        authority.publicKey
+      );
+      treasury = await createAssociatedTokenAccount(
+        provider.connection,
+        authority.payer,
+        mintKeypair.publicKey,
+        treasuryOwner.publicKey
+      );
+      await grantRole(Role.TREASURER, authority.publicKey);
+    });
+
+    it("Enforces the minimum drain delay", async () => {
+      const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+      assert.equal(tokenInfo.timelockDelays.drainLiquidity.toNumber(), DRAIN_DELAY);
+
+      try {
+        await queue(
+          3,
+          { setTimelockDelay: { kind: { drainLiquidity: {} }, delay: new anchor.BN(0) } },
+          null
+        );
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidTimelockDelay");
+      }
+    });
+
+    it("Sets the treasury only once", async () => {
+      const setTreasury = (account: PublicKey) =>
+        program.methods
+          .setTreasury()
+          .accounts({
+            authority: authority.publicKey,
+            mint: mintKeypair.publicKey,
+            tokenInfo: tokenInfoPda,
+            roleGrant: rolePda(Role.TREASURER, authority.publicKey),
+            treasury: account,
+          })
+          .rpc();
+
+      await setTreasury(treasury);
+      const tokenInfo = await program.account.tokenInfo.fetch(tokenInfoPda);
+      assert.ok(tokenInfo.treasury.equals(treasury));
+
+      try {
+        await setTreasury(liquidityPool);
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "TreasuryAlreadySet");
+      }
+    });
+
+    it("Rejects drains from unregistered pools", async () => {
+      try {
+        await queue(3, drain(EPOCH_CAP, treasury), rolePda(Role.TREASURER, authority.publicKey), {
+          liquidityPool,
+          treasury,
+          treasuryPool: null,
+        });
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "PoolNotRegistered");
+      }
+    });
+
+    it("Rejects pool registration without the Treasurer role", async () => {
+      const outsider = Keypair.generate();
+      try {
+        await program.methods
+          .registerTreasuryPool(EPOCH_CAP)
+          .accounts({
+            authority: outsider.publicKey,
+            mint: mintKeypair.publicKey,
+            tokenInfo: tokenInfoPda,
+            roleGrant: rolePda(Role.TREASURER, outsider.publicKey),
+            timelockAuthority: timelockAuthorityPda,
+            liquidityPool,
+            treasuryPool: treasuryPoolPda(liquidityPool),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([outsider])
+          .rpc();
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "AccountNotInitialized");
+      }
+    });
+
+    it("Registers a pool with an epoch cap", async () => {
+      await program.methods
+        .registerTreasuryPool(EPOCH_CAP)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.TREASURER, authority.publicKey),
+          timelockAuthority: timelockAuthorityPda,
+          liquidityPool,
+          treasuryPool: treasuryPoolPda(liquidityPool),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const treasuryPool = await program.account.treasuryPool.fetch(
+        treasuryPoolPda(liquidityPool)
+      );
+      assert.ok(treasuryPool.pool.equals(liquidityPool));
+      assert.ok(treasuryPool.epochCap.eq(EPOCH_CAP));
+      assert.equal(treasuryPool.epochOutflow.toNumber(), 0);
+
+      // The registering wallet can no longer move pool funds itself
+      const pool = await getAccount(provider.connection, liquidityPool);
+      assert.ok(pool.owner.equals(timelockAuthorityPda));
+      assert.isNull(pool.delegate);
+    });
+
+    it("Rejects drains to anything but the treasury", async () => {
+      try {
+        await queue(3, drain(EPOCH_CAP, liquidityPool), rolePda(Role.TREASURER, authority.publicKey), {
+          liquidityPool,
+          treasury: liquidityPool,
+          treasuryPool: treasuryPoolPda(liquidityPool),
+        });
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "TreasuryMismatch");
+      }
+    });
+
+    it("Rejects drains above the epoch cap", async () => {
+      try {
+        await queue(
+          3,
+          drain(EPOCH_CAP.addn(1), treasury),
+          rolePda(Role.TREASURER, authority.publicKey),
+          { liquidityPool, treasury, treasuryPool: treasuryPoolPda(liquidityPool) }
+        );
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "EpochOutflowCapExceeded");
+      }
+    });
+
+    it("Queues a drain behind the mandatory delay", async () => {
+      await queue(3, drain(EPOCH_CAP, treasury), rolePda(Role.TREASURER, authority.publicKey), {
+        liquidityPool,
+        treasury,
+        treasuryPool: treasuryPoolPda(liquidityPool),
+      });
+
+      const queued = await program.account.queuedAction.fetch(queuedActionPda(3));
+      assert.equal(queued.eta.toNumber() - queued.queuedAt.toNumber(), DRAIN_DELAY);
+
+      try {
+        await program.methods
+          .executeQueuedAction()
+          .accounts({
+            executor: authority.publicKey,
+            queuedBy: authority.publicKey,
+            mint: mintKeypair.publicKey,
+            tokenInfo: tokenInfoPda,
+            queuedAction: queuedActionPda(3),
+            mintAuthority: mintAuthorityPda,
+            timelockAuthority: timelockAuthorityPda,
+            destination: null,
+            destinationWhitelist: null,
+            bucket: null,
+            liquidityPool,
+            treasury,
+            treasuryPool: treasuryPoolPda(liquidityPool),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+        assert.fail("Expected transaction to fail");
+      } catch (error) {
+        assert.include(error.toString(), "TimelockNotElapsed");
+      }
+    });
+
+    it("Cancels a queued drain without leaving an allowance behind", async () => {
+      await program.methods
+        .cancelQueuedAction()
+        .accounts({
+          authority: authority.publicKey,
+          queuedBy: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          queuedAction: queuedActionPda(3),
+        })
+        .rpc();
+
+      assert.isNull(await provider.connection.getAccountInfo(queuedActionPda(3)));
+      const pool = await getAccount(provider.connection, liquidityPool);
+      assert.ok(pool.owner.equals(timelockAuthorityPda));
+      assert.isNull(pool.delegate);
+      assert.equal(pool.delegatedAmount, BigInt(0));
+    });
+
+    const deregisterAccounts = (signer: PublicKey) => ({
+      authority: signer,
+      mint: mintKeypair.publicKey,
+      tokenInfo: tokenInfoPda,
+      roleGrant: rolePda(Role.TREASURER, signer),
+      timelockAuthority: timelockAuthorityPda,
+      liquidityPool,
+      treasuryPool: treasuryPoolPda(liquidityPool),
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+    it("Rejects pool deregistration without the Treasurer role", async () => {
+      try {
+        await program.methods
+          .deregisterTreasuryPool()
+          .accounts(deregisterAccounts(treasuryOwner.publicKey))
+          .signers([treasuryOwner])
+          .rpc();
+        assert.fail("Should have thrown error");
+      } catch (error) {
+        assert.include(error.toString(), "AccountNotInitialized");
+      }
+    });
+
+    it("Hands an empty pool back to the treasurer on deregistration", async () => {
+      await program.methods
+        .deregisterTreasuryPool()
+        .accounts(deregisterAccounts(authority.publicKey))
+        .rpc();
+
+      assert.isNull(await provider.connection.getAccountInfo(treasuryPoolPda(liquidityPool)));
+      const pool = await getAccount(provider.connection, liquidityPool);
+      assert.ok(pool.owner.equals(authority.publicKey));
+    });
+  });
+});
@@ -0,0 +1,463 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { DollarToken } from "../target/types/dollar_token";
+import { PublicKey, Keypair, SystemProgram, Transaction } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  createAssociatedTokenAccountInstruction,
+  createTransferCheckedWithTransferHookInstruction,
+  getAccount,
+  getMint,
+  getTransferFeeAmount,
+  getTransferFeeConfig,
+  getTransferHook,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+// 100,000,000 TDL with 9 decimals
+const MAX_SUPPLY = new anchor.BN("100000000000000000");
+// 1% withheld by the transfer fee extension, at most 1 TDL per transfer
+const TRANSFER_FEE_BPS = 100;
+const MAXIMUM_FEE = new anchor.BN(1_000_000_000);
+
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
+const metadataPda = (mint: PublicKey) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+
+// Role discriminants double as the role byte in grant PDA seeds
+const Role = {
+  MINTER: 0,
+  BURNER: 1,
+  COMPLIANCE_OFFICER: 2,
+  PAUSER: 3,
+  TREASURER: 4,
+  ROLE_ADMIN: 5,
+};
+const ROLE_ARGS = [
+  { minter: {} },
+  { burner: {} },
+  { complianceOfficer: {} },
+  { pauser: {} },
+  { treasurer: {} },
+  { roleAdmin: {} },
+];
+
+// Bucket discriminants double as the category byte in bucket PDA seeds
+const Bucket = {
+  PRESALE: 0,
+  LIQUIDITY: 1,
+  REWARDS: 2,
+  TEAM: 3,
+  MARKETING: 4,
+};
+const BUCKET_ARGS = [
+  { presale: {} },
+  { liquidity: {} },
+  { rewards: {} },
+  { team: {} },
+  { marketing: {} },
+];
+
+describe("Token-2022 transfer hook", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.DollarToken as Program<DollarToken>;
+  const authority = provider.wallet as anchor.Wallet;
+  const mintKeypair = Keypair.generate();
+  const recipient = Keypair.generate();
+
+  let tokenInfoPda: PublicKey;
+  let mintAuthorityPda: PublicKey;
+  let extraAccountMetaListPda: PublicKey;
+  let authorityTokenAccount: PublicKey;
+  let recipientTokenAccount: PublicKey;
+
+  const rolePda = (role: number, holder: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("role"),
+        mintKeypair.publicKey.toBuffer(),
+        Buffer.from([role]),
+        holder.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  const whitelistPda = (wallet: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("whitelist"), mintKeypair.publicKey.toBuffer(), wallet.toBuffer()],
+      program.programId
+    )[0];
+
+  const bucketPda = (category: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("bucket"), mintKeypair.publicKey.toBuffer(), Buffer.from([category])],
+      program.programId
+    )[0];
+
+  // Creates a bucket paying into `destination` and releases `released` of it
+  const openBucket = async (
+    category: number,
+    cap: anchor.BN,
+    destination: PublicKey,
+    released: anchor.BN
+  ) => {
+    await program.methods
+      .createAllocationBucket(BUCKET_ARGS[category] as any, cap, destination)
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        roleGrant: rolePda(Role.MINTER, authority.publicKey),
+        bucket: bucketPda(category),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .releaseAllocationBucket(released)
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        roleGrant: rolePda(Role.MINTER, authority.publicKey),
+        bucket: bucketPda(category),
+      })
+      .rpc();
+  };
+
+  const grantRole = (role: number, holder: PublicKey) =>
+    program.methods
+      .grantRole(ROLE_ARGS[role] as any, holder, null)
+      .accounts({
+        admin: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        adminGrant: null,
+        roleGrant: rolePda(role, holder),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  // Plain Token-2022 transfer; the client resolves the hook's extra accounts
+  const transfer = async (amount: bigint) => {
+    const ix = await createTransferCheckedWithTransferHookInstruction(
+      provider.connection,
+      authorityTokenAccount,
+      mintKeypair.publicKey,
+      recipientTokenAccount,
+      authority.publicKey,
+      amount,
+      9,
+      [],
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    await provider.sendAndConfirm(new Transaction().add(ix));
+  };
+
+  before(async () => {
+    [tokenInfoPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("token_info"), mintKeypair.publicKey.toBuffer()],
+      program.programId
+    );
+    [mintAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_authority"), mintKeypair.publicKey.toBuffer()],
+      program.programId
+    );
+    [extraAccountMetaListPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("extra-account-metas"), mintKeypair.publicKey.toBuffer()],
+      program.programId
+    );
+    authorityTokenAccount = getAssociatedTokenAddressSync(
+      mintKeypair.publicKey,
+      authority.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    recipientTokenAccount = getAssociatedTokenAddressSync(
+      mintKeypair.publicKey,
+      recipient.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+  });
+
+  it("Creates a Token-2022 mint whose hook points at the program", async () => {
+    await program.methods
+      .initializeToken2022(
+        "Dollar Token",
+        "TDL",
+        "https://example.com/metadata.json",
+        9,
+        MAX_SUPPLY,
+        TRANSFER_FEE_BPS,
+        MAXIMUM_FEE
+      )
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        mintAuthority: mintAuthorityPda,
+        tokenInfo: tokenInfoPda,
+        extraAccountMetaList: extraAccountMetaListPda,
+        metadata: metadataPda(mintKeypair.publicKey),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([mintKeypair])
+      .rpc();
+
+    const mintAccount = await getMint(
+      provider.connection,
+      mintKeypair.publicKey,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    const hook = getTransferHook(mintAccount);
+    assert.equal(hook.programId.toString(), program.programId.toString());
+    assert.equal(hook.authority.toString(), mintAuthorityPda.toString());
+    const feeConfig = getTransferFeeConfig(mintAccount);
+    assert.equal(feeConfig.newerTransferFee.transferFeeBasisPoints, TRANSFER_FEE_BPS);
+    assert.equal(
+      feeConfig.withdrawWithheldAuthority.toString(),
+      mintAuthorityPda.toString()
+    );
+
+    await grantRole(Role.MINTER, authority.publicKey);
+    await grantRole(Role.COMPLIANCE_OFFICER, authority.publicKey);
+    await grantRole(Role.TREASURER, authority.publicKey);
+
+    const createAccountsTx = new Transaction().add(
+      createAssociatedTokenAccountInstruction(
+        authority.publicKey,
+        authorityTokenAccount,
+        authority.publicKey,
+        mintKeypair.publicKey,
+        TOKEN_2022_PROGRAM_ID
+      ),
+      createAssociatedTokenAccountInstruction(
+        authority.publicKey,
+        recipientTokenAccount,
+        recipient.publicKey,
+        mintKeypair.publicKey,
+        TOKEN_2022_PROGRAM_ID
+      )
+    );
+    await provider.sendAndConfirm(createAccountsTx);
+
+    await openBucket(
+      Bucket.PRESALE,
+      MAX_SUPPLY.divn(4),
+      authorityTokenAccount,
+      new anchor.BN(1_000_000_000_000)
+    );
+
+    await program.methods
+      .mintTokens(new anchor.BN(1_000_000_000_000))
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        mintAuthority: mintAuthorityPda,
+        tokenInfo: tokenInfoPda,
+        roleGrant: rolePda(Role.MINTER, authority.publicKey),
+        bucket: bucketPda(Bucket.PRESALE),
+        destination: authorityTokenAccount,
+        destinationWhitelist: whitelistPda(authority.publicKey),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+  });
+
+  it("Lets plain transfers through when no policy applies", async () => {
+    await transfer(BigInt(1_000_000_000));
+
+    const account = await getAccount(
+      provider.connection,
+      recipientTokenAccount,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    // The fee extension withholds 1% in the recipient's account
+    assert.equal(account.amount.toString(), "990000000");
+    assert.equal(getTransferFeeAmount(account).withheldAmount.toString(), "10000000");
+  });
+
+  it("Harvests withheld fees and withdraws them to the fee collector", async () => {
+    await program.methods
+      .configureTransferFee(TRANSFER_FEE_BPS, MAXIMUM_FEE)
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        roleGrant: rolePda(Role.TREASURER, authority.publicKey),
+        mintAuthority: mintAuthorityPda,
+        feeCollector: authorityTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    await program.methods
+      .harvestWithheld()
+      .accounts({
+        mint: mintKeypair.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: recipientTokenAccount, isSigner: false, isWritable: true },
+      ])
+      .rpc();
+
+    const mintAccount = await getMint(
+      provider.connection,
+      mintKeypair.publicKey,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(getTransferFeeConfig(mintAccount).withheldAmount.toString(), "10000000");
+
+    const balance = async () =>
+      (
+        await getAccount(provider.connection, authorityTokenAccount, "confirmed", TOKEN_2022_PROGRAM_ID)
+      ).amount;
+    const before = await balance();
+
+    const withdraw = () =>
+      program.methods
+        .withdrawWithheld()
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.TREASURER, authority.publicKey),
+          mintAuthority: mintAuthorityPda,
+          feeCollector: authorityTokenAccount,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+    await withdraw();
+    assert.equal((await balance()) - before, BigInt(10_000_000));
+
+    try {
+      await withdraw();
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "NoWithheldFees");
+    }
+  });
+
+  it("Records whitelist receipts net of the withheld fee", async () => {
+    const setWhitelistMode = (enabled: boolean) =>
+      program.methods
+        .toggleWhitelistMode(enabled)
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+        })
+        .rpc();
+
+    for (const wallet of [authority.publicKey, recipient.publicKey]) {
+      await program.methods
+        .addToWhitelist(wallet, new anchor.BN(1_000_000_000_000))
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+          whitelist: whitelistPda(wallet),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    await setWhitelistMode(true);
+    await transfer(BigInt(1_000_000_000));
+    await setWhitelistMode(false);
+
+    // 1% of the transfer stays withheld in the recipient's account
+    const entry = await program.account.whitelistEntry.fetch(whitelistPda(recipient.publicKey));
+    assert.equal(entry.purchased.toString(), "990000000");
+  });
+
+  it("Rejects controlled_transfer on a Token-2022 mint", async () => {
+    const pda = (prefix: string, key: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from(prefix), mintKeypair.publicKey.toBuffer(), key.toBuffer()],
+        program.programId
+      )[0];
+
+    try {
+      await program.methods
+        .controlledTransfer(new anchor.BN(1_000_000_000), {
+          senderWhitelist: [],
+          recipientWhitelist: [],
+          senderExclusion: null,
+          recipientExclusion: null,
+        })
+        .accounts({
+          authority: authority.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenInfo: tokenInfoPda,
+          from: authorityTokenAccount,
+          to: recipientTokenAccount,
+          senderBlacklist: pda("blacklist", authority.publicKey),
+          recipientBlacklist: pda("blacklist", recipient.publicKey),
+          senderWhitelist: whitelistPda(authority.publicKey),
+          recipientWhitelist: whitelistPda(recipient.publicKey),
+          senderLimitExemption: pda("limit_exemption", authorityTokenAccount),
+          recipientLimitExemption: pda("limit_exemption", recipientTokenAccount),
+          senderFeeExemption: pda("fee_exemption", authorityTokenAccount),
+          recipientFeeExemption: pda("fee_exemption", recipientTokenAccount),
+          feeCollector: null,
+          senderVelocity: pda("velocity", authority.publicKey),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      assert.include(error.toString(), "Token2022TransferUnsupported");
+    }
+  });
+
+  it("Blocks plain transfers to a blacklisted wallet", async () => {
+    const [blacklistPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("blacklist"),
+        mintKeypair.publicKey.toBuffer(),
+        recipient.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .addToBlacklist(recipient.publicKey, 1, "", null, Array(32).fill(0))
+      .accounts({
+        authority: authority.publicKey,
+        mint: mintKeypair.publicKey,
+        tokenInfo: tokenInfoPda,
+        roleGrant: rolePda(Role.COMPLIANCE_OFFICER, authority.publicKey),
+        blacklist: blacklistPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    try {
+      await transfer(BigInt(1_000_000_000));
+      assert.fail("Expected transaction to fail");
+    } catch (error) {
+      // Hook errors surface in the program logs of the failed transfer
+      assert.include((error.logs ?? []).join("\n"), "RecipientBlacklisted");
+    }
+  });
+});
@@ -0,0 +1,397 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TdlVesting } from "../target/types/tdl_vesting";
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  LAMPORTS_PER_SOL,
+  Transaction,
+} from "@solana/web3.js";
+import {
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMint,
+  getMintLen,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+// 1% withheld by the transfer fee extension, like the Token-2022 TDL mint
+const TRANSFER_FEE_BPS = 100;
+
+describe("Vesting", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.TdlVesting as Program<TdlVesting>;
+  const admin = provider.wallet as anchor.Wallet;
+
+  const TOTAL = new anchor.BN(1_000_000);
+
+  let mint: PublicKey;
+  let adminTokenAccount: PublicKey;
+
+  const grantPda = (beneficiary: PublicKey, grantAdmin = admin.publicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("grant"), mint.toBuffer(), grantAdmin.toBuffer(), beneficiary.toBuffer()],
+      program.programId
+    )[0];
+
+  const vaultPda = (grant: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), grant.toBuffer()],
+      program.programId
+    )[0];
+
+  const tokenBalance = async (account: PublicKey) =>
+    Number((await getAccount(provider.connection, account)).amount);
+
+  const fundedBeneficiary = async () => {
+    const beneficiary = Keypair.generate();
+    const sig = await provider.connection.requestAirdrop(
+      beneficiary.publicKey,
+      LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(sig);
+    const tokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      admin.payer,
+      mint,
+      beneficiary.publicKey
+    );
+    return { beneficiary, tokenAccount: tokenAccount.address };
+  };
+
+  const createGrant = async (
+    beneficiary: PublicKey,
+    schedule: { cliff: number; duration: number; period?: number },
+    revocable = true
+  ) => {
+    const grant = grantPda(beneficiary);
+    await program.methods
+      .createGrant(
+        TOTAL,
+        {
+          startTs: new anchor.BN(Math.floor(Date.now() / 1000)),
+          cliffSeconds: new anchor.BN(schedule.cliff),
+          durationSeconds: new anchor.BN(schedule.duration),
+          periodSeconds: new anchor.BN(schedule.period ?? 0),
+        },
+        revocable
+      )
+      .accounts({
+        admin: admin.publicKey,
+        beneficiary,
+        mint,
+        grant,
+        vault: vaultPda(grant),
+        adminTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    return grant;
+  };
+
+  const release = (beneficiary: Keypair, beneficiaryTokenAccount: PublicKey) => {
+    const grant = grantPda(beneficiary.publicKey);
+    return program.methods
+      .release()
+      .accounts({
+        beneficiary: beneficiary.publicKey,
+        mint,
+        grant,
+        vault: vaultPda(grant),
+        beneficiaryTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([beneficiary])
+      .rpc();
+  };
+
+  const revoke = (grant: PublicKey) =>
+    program.methods
+      .revoke()
+      .accounts({
+        admin: admin.publicKey,
+        mint,
+        grant,
+        vault: vaultPda(grant),
+        refundAccount: adminTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+  before(async () => {
+    mint = await createMint(provider.connection, admin.payer, admin.publicKey, null, 9);
+    adminTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        admin.payer,
+        mint,
+        admin.publicKey
+      )
+    ).address;
+    await mintTo(
+      provider.connection,
+      admin.payer,
+      mint,
+      adminTokenAccount,
+      admin.payer,
+      TOTAL.toNumber() * 10
+    );
+  });
+
+  it("Should lock the grant total in the vault", async () => {
+    const { beneficiary } = await fundedBeneficiary();
+    const grant = await createGrant(beneficiary.publicKey, { cliff: 60, duration: 600 });
+
+    const grantAccount = await program.account.vestingGrant.fetch(grant);
+    assert.ok(grantAccount.beneficiary.equals(beneficiary.publicKey));
+    assert.ok(grantAccount.total.eq(TOTAL));
+    assert.equal(grantAccount.released.toNumber(), 0);
+    assert.isNull(grantAccount.revokedAt);
+    assert.equal(await tokenBalance(vaultPda(grant)), TOTAL.toNumber());
+  });
+
+  it("Should reject a cliff longer than the duration", async () => {
+    const { beneficiary } = await fundedBeneficiary();
+    try {
+      await createGrant(beneficiary.publicKey, { cliff: 700, duration: 600 });
+      assert.fail("Should have thrown an error");
+    } catch (error) {
+      assert.include(error.message, "InvalidSchedule");
+    }
+  });
+
+  it("Should release nothing before the cliff", async () => {
+    const { beneficiary, tokenAccount } = await fundedBeneficiary();
+    await createGrant(beneficiary.publicKey, { cliff: 60, duration: 600 });
+
+    try {
+      await release(beneficiary, tokenAccount);
+      assert.fail("Should have thrown an error");
+    } catch (error) {
+      assert.include(error.message, "NothingToRelease");
+    }
+  });
+
+  it("Should release the full total once the duration has passed", async () => {
+    const { beneficiary, tokenAccount } = await fundedBeneficiary();
+    const grant = await createGrant(beneficiary.publicKey, { cliff: 1, duration: 2 });
+
+    await sleep(3000);
+    await release(beneficiary, tokenAccount);
+
+    assert.equal(await tokenBalance(tokenAccount), TOTAL.toNumber());
+    const grantAccount = await program.account.vestingGrant.fetch(grant);
+    assert.ok(grantAccount.released.eq(TOTAL));
+  });
+
+  it("Should refund only unvested tokens on revoke", async () => {
+    const { beneficiary, tokenAccount } = await fundedBeneficiary();
+    const grant = await createGrant(beneficiary.publicKey, {
+      cliff: 2,
+      duration: 1000,
+      period: 2,
+    });
+
+    await sleep(3000);
+    const adminBefore = await tokenBalance(adminTokenAccount);
+    await revoke(grant);
+
+    const grantAccount = await program.account.vestingGrant.fetch(grant);
+    assert.isNotNull(grantAccount.revokedAt);
+    const refunded = (await tokenBalance(adminTokenAccount)) - adminBefore;
+    const vested = TOTAL.toNumber() - refunded;
+    assert.isAbove(vested, 0);
+    assert.isAbove(refunded, 0);
+    assert.equal(await tokenBalance(vaultPda(grant)), vested);
+
+    // Vesting stops at revocation, so the beneficiary can only take what was vested
+    await sleep(2000);
+    await release(beneficiary, tokenAccount);
+    assert.equal(await tokenBalance(tokenAccount), vested);
+    assert.equal(await tokenBalance(vaultPda(grant)), 0);
+
+    try {
+      await revoke(grant);
+      assert.fail("Should have thrown an error");
+    } catch (error) {
+      assert.include(error.message, "AlreadyRevoked");
+    }
+  });
+
+  it("Should keep another wallet's grant from blocking the admin's", async () => {
+    const { beneficiary } = await fundedBeneficiary();
+    const { beneficiary: squatter, tokenAccount: squatterTokenAccount } =
+      await fundedBeneficiary();
+    await mintTo(provider.connection, admin.payer, mint, squatterTokenAccount, admin.payer, 1);
+
+    const squatterGrant = grantPda(beneficiary.publicKey, squatter.publicKey);
+    await program.methods
+      .createGrant(
+        new anchor.BN(1),
+        {
+          startTs: new anchor.BN(Math.floor(Date.now() / 1000)),
+          cliffSeconds: new anchor.BN(0),
+          durationSeconds: new anchor.BN(1),
+          periodSeconds: new anchor.BN(0),
+        },
+        false
+      )
+      .accounts({
+        admin: squatter.publicKey,
+        beneficiary: beneficiary.publicKey,
+        mint,
+        grant: squatterGrant,
+        vault: vaultPda(squatterGrant),
+        adminTokenAccount: squatterTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([squatter])
+      .rpc();
+
+    const grant = await createGrant(beneficiary.publicKey, { cliff: 60, duration: 600 });
+    assert.notOk(grant.equals(squatterGrant));
+    const grantAccount = await program.account.vestingGrant.fetch(grant);
+    assert.ok(grantAccount.admin.equals(admin.publicKey));
+    assert.ok(grantAccount.total.eq(TOTAL));
+  });
+
+  it("Should not revoke an irrevocable grant", async () => {
+    const { beneficiary } = await fundedBeneficiary();
+    const grant = await createGrant(beneficiary.publicKey, { cliff: 60, duration: 600 }, false);
+
+    try {
+      await revoke(grant);
+      assert.fail("Should have thrown an error");
+    } catch (error) {
+      assert.include(error.message, "NotRevocable");
+    }
+  });
+
+  it("Should vest only what reaches the vault on a fee-bearing mint", async () => {
+    const feeMint = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: admin.publicKey,
+          newAccountPubkey: feeMint.publicKey,
+          space: mintLen,
+          lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          feeMint.publicKey,
+          admin.publicKey,
+          admin.publicKey,
+          TRANSFER_FEE_BPS,
+          BigInt(TOTAL.toString()),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(
+          feeMint.publicKey,
+          9,
+          admin.publicKey,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ),
+      [feeMint]
+    );
+    const tokenAccount = (owner: PublicKey) =>
+      getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        admin.payer,
+        feeMint.publicKey,
+        owner,
+        false,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      ).then((account) => account.address);
+    const feeAdminAccount = await tokenAccount(admin.publicKey);
+    await mintTo(
+      provider.connection,
+      admin.payer,
+      feeMint.publicKey,
+      feeAdminAccount,
+      admin.payer,
+      TOTAL.toNumber(),
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const beneficiary = Keypair.generate();
+    const [grant] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("grant"),
+        feeMint.publicKey.toBuffer(),
+        admin.publicKey.toBuffer(),
+        beneficiary.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    await program.methods
+      .createGrant(
+        TOTAL,
+        {
+          startTs: new anchor.BN(Math.floor(Date.now() / 1000)),
+          cliffSeconds: new anchor.BN(0),
+          durationSeconds: new anchor.BN(1),
+          periodSeconds: new anchor.BN(0),
+        },
+        true
+      )
+      .accounts({
+        admin: admin.publicKey,
+        beneficiary: beneficiary.publicKey,
+        mint: feeMint.publicKey,
+        grant,
+        vault: vaultPda(grant),
+        adminTokenAccount: feeAdminAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const received = TOTAL.muln(10_000 - TRANSFER_FEE_BPS).divn(10_000);
+    const grantAccount = await program.account.vestingGrant.fetch(grant);
+    assert.equal(grantAccount.total.toString(), received.toString());
+
+    // The last tranche is exactly what the vault holds
+    await sleep(2000);
+    await program.methods
+      .release()
+      .accounts({
+        beneficiary: beneficiary.publicKey,
+        mint: feeMint.publicKey,
+        grant,
+        vault: vaultPda(grant),
+        beneficiaryTokenAccount: await tokenAccount(beneficiary.publicKey),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([beneficiary])
+      .rpc();
+
+    const vault = await getAccount(
+      provider.connection,
+      vaultPda(grant),
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(vault.amount, BigInt(0));
+  });
+});